@@ -0,0 +1,406 @@
+//! Minimum spanning arborescence (Chu–Liu/Edmonds)
+//! <https://en.wikipedia.org/wiki/Edmonds%27_algorithm>
+//!
+//! Uses Tarjan's variant: every vertex keeps a mergeable heap of its incoming
+//! edges, and cycles are contracted with a union-find that can be rolled back
+//! to expand them again.
+use super::{bfs::bfs, EdgeCount, Graph, IntoNeighbors, VertexCount};
+
+impl Graph {
+    /// Returns the parent edges of a minimum spanning arborescence. See
+    /// [`min_arborescence`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the vertices that can't be reached from `root`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`.
+    pub fn min_arborescence(
+        &self,
+        weights: &[i64],
+        root: usize,
+    ) -> Result<Vec<Option<usize>>, Vec<usize>> {
+        min_arborescence(self, weights, root)
+    }
+}
+
+/// Returns a minimum-weight set of edges through which `root` reaches every
+/// vertex, as the edge entering each vertex. The root has no parent edge.
+///
+/// Each vertex picks its cheapest incoming edge. When the picks close a
+/// cycle, it's contracted to a single vertex whose incoming edges cost what
+/// they'd save over the cycle edge they replace. Self-loops and edges into
+/// the root are never used. Weights may be negative.
+/// Time complexity: O(|E| log |V|)
+///
+/// # Errors
+///
+/// Returns the vertices that can't be reached from `root`, in increasing
+/// order.
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`.
+pub fn min_arborescence<G>(
+    graph: G,
+    weights: &[i64],
+    root: usize,
+) -> Result<Vec<Option<usize>>, Vec<usize>>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    let n = graph.vertex_count();
+    let mut edges = vec![];
+    let mut heap = SkewHeap::default();
+    let mut incoming = vec![None; n];
+    for u in 0..n {
+        for (v, e) in graph.neighbors(u) {
+            if u != v && v != root {
+                let node = heap.push(weights[e], edges.len());
+                incoming[v] = heap.merge(incoming[v], Some(node));
+                edges.push((u, v, e));
+            }
+        }
+    }
+
+    let mut components = RollbackUnionFind::new(n);
+    let mut seen = vec![None; n];
+    seen[root] = Some(root);
+    let mut chosen = vec![None; n];
+    let mut cycles = vec![];
+    for s in 0..n {
+        let mut u = s;
+        let (mut queue, mut path) = (vec![], vec![]);
+        while seen[u].is_none() {
+            // `u` is unreachable from the root along with everything that
+            // was contracted into it
+            let Some(top) = incoming[u] else {
+                let mut reached = vec![false; n];
+                for (v, _) in bfs(graph, [root]) {
+                    reached[v] = true;
+                }
+                return Err((0..n).filter(|&v| !reached[v]).collect());
+            };
+            let (weight, i) = heap.top(top);
+            heap.add(top, -weight);
+            incoming[u] = heap.pop(top);
+            queue.push(i);
+            path.push(u);
+            seen[u] = Some(s);
+            u = components.find(edges[i].0);
+            if seen[u] == Some(s) {
+                let time = components.time();
+                let mut cycle = None;
+                while let Some(w) = path.pop() {
+                    cycle = heap.merge(cycle, incoming[w]);
+                    if !components.union(u, w) {
+                        break;
+                    }
+                }
+                u = components.find(u);
+                incoming[u] = cycle;
+                seen[u] = None;
+                cycles.push((u, time, queue.split_off(path.len())));
+            }
+        }
+        for i in queue {
+            chosen[components.find(edges[i].1)] = Some(i);
+        }
+    }
+
+    // Expand the cycles in reverse: every cycle edge is kept, except the one
+    // into the vertex that the cycle's own incoming edge enters
+    for (u, time, cycle) in cycles.into_iter().rev() {
+        components.rollback(time);
+        let entering = chosen[u];
+        for i in cycle {
+            chosen[components.find(edges[i].1)] = Some(i);
+        }
+        if let Some(i) = entering {
+            chosen[components.find(edges[i].1)] = Some(i);
+        }
+    }
+    chosen[root] = None;
+    Ok(chosen.into_iter().map(|i| i.map(|i| edges[i].2)).collect())
+}
+
+/// Skew heaps stored in one arena, with lazy addition to every key.
+#[derive(Default)]
+struct SkewHeap {
+    /// The `(key, value)` of each node.
+    items: Vec<(i64, usize)>,
+    /// The amount to add to every key in each subtree.
+    pending: Vec<i64>,
+    /// The children of each node.
+    children: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl SkewHeap {
+    /// Creates a heap of one node, and returns the node.
+    fn push(&mut self, key: i64, value: usize) -> usize {
+        self.items.push((key, value));
+        self.pending.push(0);
+        self.children.push((None, None));
+        self.items.len() - 1
+    }
+
+    /// Applies the pending addition at `node` to its key and children.
+    fn settle(&mut self, node: usize) {
+        let delta = std::mem::take(&mut self.pending[node]);
+        self.items[node].0 += delta;
+        for child in <[_; 2]>::from(self.children[node]).into_iter().flatten() {
+            self.pending[child] += delta;
+        }
+    }
+
+    /// Returns the smallest `(key, value)` of the heap rooted at `node`.
+    fn top(&mut self, node: usize) -> (i64, usize) {
+        self.settle(node);
+        self.items[node]
+    }
+
+    /// Adds `delta` to every key of the heap rooted at `node`.
+    fn add(&mut self, node: usize, delta: i64) {
+        self.pending[node] += delta;
+    }
+
+    /// Removes the root of a heap, and returns the new root.
+    fn pop(&mut self, node: usize) -> Option<usize> {
+        self.settle(node);
+        let (left, right) = self.children[node];
+        self.merge(left, right)
+    }
+
+    /// Merges two heaps, and returns the new root.
+    fn merge(&mut self, mut a: Option<usize>, mut b: Option<usize>) -> Option<usize> {
+        // Walk down the right spines, then swap children on the way back up
+        let mut spine = vec![];
+        let mut rest = loop {
+            match (a, b) {
+                (Some(x), Some(y)) => {
+                    self.settle(x);
+                    self.settle(y);
+                    let (x, y) = if self.items[x] <= self.items[y] {
+                        (x, y)
+                    } else {
+                        (y, x)
+                    };
+                    spine.push(x);
+                    (a, b) = (self.children[x].1, Some(y));
+                }
+                (x, None) | (None, x) => break x,
+            }
+        };
+        while let Some(x) = spine.pop() {
+            let (left, _) = self.children[x];
+            self.children[x] = (rest, left);
+            rest = Some(x);
+        }
+        rest
+    }
+}
+
+/// Union by size without path compression, so unions can be undone.
+struct RollbackUnionFind {
+    /// The parent of each node, or the node itself if it's a root.
+    parent: Vec<usize>,
+    /// The number of nodes in the set of each root.
+    size: Vec<usize>,
+    /// The roots that were attached to another root, in order.
+    history: Vec<usize>,
+}
+
+impl RollbackUnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            history: vec![],
+        }
+    }
+
+    fn find(&self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            node = self.parent[node];
+        }
+        node
+    }
+
+    /// Joins the sets of `a` and `b`, and returns false if they were already
+    /// joined.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.history.push(b);
+        true
+    }
+
+    /// Returns a point in time to roll back to.
+    const fn time(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes every union made after `time`.
+    fn rollback(&mut self, time: usize) {
+        while self.history.len() > time {
+            let Some(b) = self.history.pop() else {
+                break;
+            };
+            let a = self.parent[b];
+            self.size[a] -= self.size[b];
+            self.parent[b] = b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generators::gnp;
+
+    /// Returns the total weight of `parent` if it's an arborescence rooted at
+    /// `root`.
+    fn tree_weight(
+        edges: &[(usize, usize, usize)],
+        weights: &[i64],
+        root: usize,
+        parent: &[Option<usize>],
+    ) -> Option<i64> {
+        let mut total = 0;
+        for (v, &e) in parent.iter().enumerate() {
+            match e {
+                None if v == root => {}
+                Some(e) if v != root && edges[e].1 == v => total += weights[e],
+                _ => return None,
+            }
+            // Following parent edges must reach the root
+            let mut u = v;
+            for _ in 0..parent.len() {
+                if let Some(e) = parent[u] {
+                    u = edges[e].0;
+                }
+            }
+            if u != root {
+                return None;
+            }
+        }
+        Some(total)
+    }
+
+    /// Returns the smallest arborescence weight by trying every parent edge.
+    fn brute_force(graph: &Graph, weights: &[i64], root: usize) -> Option<i64> {
+        let edges = graph.edges();
+        let mut choices = vec![vec![None]; graph.len()];
+        for v in (0..graph.len()).filter(|&v| v != root) {
+            choices[v] = (0..edges.len())
+                .filter(|&e| edges[e].1 == v && edges[e].0 != v)
+                .map(Some)
+                .collect();
+        }
+        let mut index = vec![0; graph.len()];
+        let mut best = None;
+        if choices.iter().any(Vec::is_empty) {
+            return None;
+        }
+        loop {
+            let parent = (0..graph.len())
+                .map(|v| choices[v][index[v]])
+                .collect::<Vec<_>>();
+            if let Some(w) = tree_weight(&edges, weights, root, &parent) {
+                best = Some(best.map_or(w, |b: i64| b.min(w)));
+            }
+            // Advance the odometer
+            let Some(v) = (0..graph.len()).find(|&v| index[v] + 1 < choices[v].len()) else {
+                return best;
+            };
+            index[v] += 1;
+            index[..v].fill(0);
+        }
+    }
+
+    #[test]
+    fn test_min_arborescence() {
+        /*
+            0 -> 1 (5), 0 -> 2 (9), 1 <-> 2 (1 each way),
+            2 -> 3 (2), 3 -> 1 (1), and 1 -> 1 (-9)
+        */
+        let graph = Graph::from([(0, 1), (0, 2), (1, 2), (2, 1), (2, 3), (3, 1), (1, 1)]);
+        let weights = [5, 9, 1, 1, 2, 1, -9];
+        let parent = graph.min_arborescence(&weights, 0).unwrap();
+        assert_eq!(parent, [None, Some(0), Some(2), Some(4)]);
+
+        // Entering the cycle 1 -> 2 -> 1 at 2 is now cheaper
+        let weights = [5, 3, 1, 1, 2, 1, -9];
+        let parent = graph.min_arborescence(&weights, 0).unwrap();
+        assert_eq!(parent, [None, Some(3), Some(1), Some(4)]);
+
+        assert_eq!(graph.min_arborescence(&weights, 1), Err(vec![0]));
+        assert_eq!(Graph::new(3, 0).min_arborescence(&[], 1), Err(vec![0, 2]));
+    }
+
+    #[test]
+    fn test_nested_cycles() {
+        /*
+            0 -> 1 (10), 0 -> 3 (12), 1 <-> 2 (1 each way), 2 -> 3 (2),
+            3 -> 4 (1), 4 -> 2 (1), 3 -> 1 (3), and 4 -> 0 (-50)
+
+            1 <-> 2 is contracted first, then the cycle through it, 3 and 4
+        */
+        let graph = Graph::from([
+            (0, 1),
+            (0, 3),
+            (1, 2),
+            (2, 1),
+            (2, 3),
+            (3, 4),
+            (4, 2),
+            (3, 1),
+            (4, 0),
+        ]);
+        let mut weights = [10, 12, 1, 1, 2, 1, 1, 3, -50];
+        let parent = graph.min_arborescence(&weights, 0).unwrap();
+        assert_eq!(parent, [None, Some(0), Some(2), Some(4), Some(5)]);
+
+        // Entering at 3 is now cheaper, which opens both cycles elsewhere
+        weights[1] = 10;
+        let parent = graph.min_arborescence(&weights, 0).unwrap();
+        assert_eq!(parent, [None, Some(3), Some(6), Some(1), Some(5)]);
+
+        // A cycle that can't be reached is still reported
+        let graph = Graph::from([(0, 1), (2, 3), (3, 2), (3, 1)]);
+        assert_eq!(graph.min_arborescence(&[1; 4], 0), Err(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_brute_force() {
+        for seed in 0..60_usize {
+            let n = 2 + seed % 5;
+            let graph = gnp(n, 0.5, seed as u64);
+            let edges = graph.edges();
+            let weights = (0..edges.len())
+                .map(|i| i64::try_from((i * 2_654_435_761 + seed * 97) % 13).unwrap() - 4)
+                .collect::<Vec<_>>();
+            let root = seed % n;
+            match (
+                graph.min_arborescence(&weights, root),
+                brute_force(&graph, &weights, root),
+            ) {
+                (Ok(parent), Some(best)) => {
+                    assert_eq!(tree_weight(&edges, &weights, root, &parent), Some(best));
+                }
+                (Err(unreachable), None) => assert!(!unreachable.is_empty()),
+                (result, best) => panic!("seed {seed}: {result:?} but {best:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Multi-source breadth-first search with distances and parents
+//! <https://en.wikipedia.org/wiki/Breadth-first_search>
+use super::{traversal::TraversalState, IntoNeighbors, VertexCount};
+use std::{borrow::BorrowMut, collections::VecDeque};
+
+/// Starts a breadth-first search from every vertex in `starts` at once.
+///
+/// The returned iterator yields each reachable vertex with its distance in
+/// edges from the nearest start, in level order, starting with the starts.
+pub fn bfs<G, I>(graph: G, starts: I) -> Bfs<G>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+{
+    let mut distance = vec![None; graph.vertex_count()];
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distance[start].is_none() {
+            distance[start] = Some(0);
+            queue.push_back(start);
+        }
+    }
+    Bfs {
+        graph,
+        queue,
+        tree: BfsTree {
+            distance,
+            parent: vec![None; graph.vertex_count()],
+        },
+    }
+}
+
+/// Same as [`bfs`], but keeps its visited marks in `state`, which is reset
+/// first, and doesn't record a tree. Only the vertices the search reaches cost
+/// anything.
+pub fn bfs_with<G, I, S>(graph: G, starts: I, mut state: S) -> BfsWith<G, S>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if state.borrow_mut().visit(start) {
+            queue.push_back((start, 0));
+        }
+    }
+    BfsWith {
+        graph,
+        queue,
+        state,
+    }
+}
+
+/// The shortest-path forest found by a breadth-first search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BfsTree {
+    /// Maps a vertex to its distance from the nearest start, if reachable.
+    pub distance: Vec<Option<usize>>,
+    /// Maps a vertex to the `(vertex, edge)` it was reached from.
+    /// Starts and unreachable vertices have no parent.
+    pub parent: Vec<Option<(usize, usize)>>,
+}
+
+impl BfsTree {
+    /// Returns the start that `node` is nearest to, if reachable.
+    #[must_use]
+    pub fn root(&self, mut node: usize) -> Option<usize> {
+        self.distance[node]?;
+        while let Some((u, _)) = self.parent[node] {
+            node = u;
+        }
+        Some(node)
+    }
+
+    /// Returns the edges of a shortest path from the nearest start to `node`,
+    /// if reachable.
+    #[must_use]
+    pub fn path(&self, mut node: usize) -> Option<Vec<usize>> {
+        let mut path = Vec::with_capacity(self.distance[node]?);
+        while let Some((u, e)) = self.parent[node] {
+            path.push(e);
+            node = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Iterator over `(vertex, depth)` pairs of a breadth-first search.
+pub struct Bfs<G> {
+    /// The graph that this iterator is iterating over.
+    graph: G,
+    /// queue of discovered nodes whose neighbors haven't been scanned
+    queue: VecDeque<usize>,
+    /// distances and parents of the nodes discovered so far
+    tree: BfsTree,
+}
+
+impl<G: IntoNeighbors> Bfs<G> {
+    /// Returns the distances and parents of the vertices discovered so far.
+    /// Every vertex yielded by the iterator has its final values.
+    pub const fn tree(&self) -> &BfsTree {
+        &self.tree
+    }
+
+    /// Finishes the search and returns the full shortest-path forest.
+    #[must_use]
+    pub fn into_tree(mut self) -> BfsTree {
+        self.by_ref().for_each(drop);
+        self.tree
+    }
+}
+
+impl<G: IntoNeighbors> Iterator for Bfs<G> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.queue.pop_front()?;
+        let depth = self.tree.distance[u]?;
+        for (v, e) in self.graph.neighbors(u) {
+            if self.tree.distance[v].is_none() {
+                self.tree.distance[v] = Some(depth + 1);
+                self.tree.parent[v] = Some((u, e));
+                self.queue.push_back(v);
+            }
+        }
+        Some((u, depth))
+    }
+}
+
+/// Iterator over `(vertex, depth)` pairs of a breadth-first search that
+/// reuses its visited marks. Created by [`bfs_with`].
+pub struct BfsWith<G, S> {
+    /// The graph that this iterator is iterating over.
+    graph: G,
+    /// queue of discovered nodes whose neighbors haven't been scanned, with
+    /// their depths
+    queue: VecDeque<(usize, usize)>,
+    /// marks the discovered nodes
+    state: S,
+}
+
+impl<G: IntoNeighbors, S: BorrowMut<TraversalState>> Iterator for BfsWith<G, S> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (u, depth) = self.queue.pop_front()?;
+        for (v, _) in self.graph.neighbors(u) {
+            if self.state.borrow_mut().visit(v) {
+                self.queue.push_back((v, depth + 1));
+            }
+        }
+        Some((u, depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_bfs() {
+        /*
+            0 -> 1 -> 2 -> 3 <- 4 <- 5
+                      ^---------'
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (2, 3), (4, 3), (5, 4), (4, 2)]);
+        assert_eq!(
+            bfs(&graph, [0]).collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (2, 2), (3, 3)]
+        );
+        assert_eq!(
+            bfs(&graph, [0, 5, 0]).collect::<Vec<_>>(),
+            [(0, 0), (5, 0), (1, 1), (4, 1), (2, 2), (3, 2)]
+        );
+
+        let tree = bfs(&graph, [0, 5]).into_tree();
+        assert_eq!(
+            tree.distance,
+            [Some(0), Some(1), Some(2), Some(2), Some(1), Some(0)]
+        );
+        assert_eq!(tree.parent[3], Some((4, 3)));
+        assert_eq!(tree.root(3), Some(5));
+        assert_eq!(tree.root(1), Some(0));
+        assert_eq!(tree.path(3), Some(vec![4, 3]));
+        assert_eq!(tree.path(5), Some(vec![]));
+
+        let tree = bfs(&graph, [2]).into_tree();
+        assert_eq!(tree.root(0), None);
+        assert_eq!(tree.path(0), None);
+
+        let mut state = TraversalState::default();
+        for starts in [vec![0, 5, 0], vec![0], vec![0, 5]] {
+            assert_eq!(
+                bfs_with(&graph, starts.clone(), &mut state).collect::<Vec<_>>(),
+                bfs(&graph, starts).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Centrality measures
+//! <https://en.wikipedia.org/wiki/Centrality>
+//!
+//! Distances follow edge direction, from the vertex being scored outwards.
+//! Parallel edges count as separate shortest paths.
+use super::{bfs::bfs, dijkstra::dijkstra, EdgeCount, IntoNeighbors, VertexCount};
+use std::collections::VecDeque;
+
+/// `PageRank` by power iteration.
+///
+/// A random surfer follows a random outgoing edge with probability `damping`
+/// and otherwise jumps to a random vertex; a vertex with no outgoing edges
+/// always jumps. Iterates until the ranks change by less than `tolerance` in
+/// total, or `max_iterations` times since rounding can keep a small tolerance
+/// out of reach, and returns ranks that sum to 1.
+/// <https://en.wikipedia.org/wiki/PageRank>
+/// Time complexity: O(|V| + |E|) per iteration
+///
+/// # Panics
+///
+/// Panics unless `0 <= damping < 1` and `tolerance > 0`.
+#[allow(clippy::cast_precision_loss)]
+pub fn pagerank<G>(graph: G, damping: f64, tolerance: f64, max_iterations: usize) -> Vec<f64>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    assert!((0.0..1.0).contains(&damping), "damping must be in [0, 1)");
+    assert!(tolerance > 0.0, "tolerance must be positive");
+    let n = graph.vertex_count();
+    let out_degree = (0..n)
+        .map(|u| graph.neighbors(u).count())
+        .collect::<Vec<_>>();
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..max_iterations {
+        let dangling = (0..n)
+            .filter(|&u| out_degree[u] == 0)
+            .map(|u| rank[u])
+            .sum::<f64>();
+        let base = damping.mul_add(dangling, 1.0 - damping) / n as f64;
+        let mut next = vec![base; n];
+        for u in 0..n {
+            let share = damping * rank[u] / out_degree[u].max(1) as f64;
+            for (v, _) in graph.neighbors(u) {
+                next[v] += share;
+            }
+        }
+        let change = rank
+            .iter()
+            .zip(&next)
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>();
+        rank = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    rank
+}
+
+/// Adds the dependencies of source `s` to `centrality`, given the vertices
+/// in non-decreasing distance from `s`, their predecessors on shortest paths
+/// and their numbers of shortest paths.
+fn accumulate(
+    s: usize,
+    order: &[usize],
+    predecessors: &[Vec<usize>],
+    paths: &[f64],
+    centrality: &mut [f64],
+) {
+    let mut dependency = vec![0.0; paths.len()];
+    for &w in order.iter().rev() {
+        for &v in &predecessors[w] {
+            dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+        }
+        if w != s {
+            centrality[w] += dependency[w];
+        }
+    }
+}
+
+/// Brandes' betweenness centrality, counting path lengths in edges.
+///
+/// A vertex scores the sum over pairs `s != t` of the fraction of shortest
+/// `s`-`t` paths that pass through it. Scores are not normalized.
+/// <https://en.wikipedia.org/wiki/Betweenness_centrality>
+/// Time complexity: O(|V| |E|)
+pub fn betweenness<G>(graph: G) -> Vec<f64>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    let mut centrality = vec![0.0; n];
+    for s in 0..n {
+        let mut distance = vec![None; n];
+        let mut paths = vec![0.0; n];
+        let mut predecessors = vec![vec![]; n];
+        let mut order = vec![];
+        let mut queue = VecDeque::from([s]);
+        distance[s] = Some(0);
+        paths[s] = 1.0;
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            let next = distance[u].map(|d| d + 1);
+            for (v, _) in graph.neighbors(u) {
+                if distance[v].is_none() {
+                    distance[v] = next;
+                    queue.push_back(v);
+                }
+                if distance[v] == next {
+                    paths[v] += paths[u];
+                    predecessors[v].push(u);
+                }
+            }
+        }
+        accumulate(s, &order, &predecessors, &paths, &mut centrality);
+    }
+    centrality
+}
+
+/// Brandes' betweenness centrality with edge weights. See [`betweenness`].
+///
+/// Zero-weight edges are allowed: a vertex is only scored once every
+/// shortest path into it is counted, so ties in distance don't matter.
+/// Time complexity: O(|V| |E| log |V|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`, or if a cycle of
+/// zero-weight edges lies on a shortest path, since then there are infinitely
+/// many.
+pub fn weighted_betweenness<G>(graph: G, weights: &[u64]) -> Vec<f64>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    let n = graph.vertex_count();
+    let mut centrality = vec![0.0; n];
+    for s in 0..n {
+        let dist = dijkstra(graph, weights, s);
+        // Whether edge `e` from `u` to `v` is on a shortest path
+        let tight = |u: usize, v: usize, e: usize| {
+            dist[u] != u64::MAX && dist[u].checked_add(weights[e]) == Some(dist[v])
+        };
+        let mut predecessors = vec![vec![]; n];
+        for u in 0..n {
+            for (v, e) in graph.neighbors(u) {
+                if tight(u, v, e) {
+                    predecessors[v].push(u);
+                }
+            }
+        }
+
+        // Visit the shortest-path edges in topological order
+        let mut pending = predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut paths = vec![0.0; n];
+        let mut order = vec![];
+        let mut ready = if pending[s] == 0 { vec![s] } else { vec![] };
+        paths[s] = 1.0;
+        while let Some(u) = ready.pop() {
+            order.push(u);
+            for (v, e) in graph.neighbors(u) {
+                if tight(u, v, e) {
+                    paths[v] += paths[u];
+                    pending[v] -= 1;
+                    if pending[v] == 0 {
+                        ready.push(v);
+                    }
+                }
+            }
+        }
+        let reached = dist.iter().filter(|&&d| d != u64::MAX).count();
+        assert!(
+            order.len() == reached,
+            "shortest paths from {s} pass through a zero-weight cycle"
+        );
+        accumulate(s, &order, &predecessors, &paths, &mut centrality);
+    }
+    centrality
+}
+
+/// Closeness centrality.
+///
+/// A vertex scores the inverse of its mean distance to the vertices it
+/// reaches, scaled by the fraction of other vertices it reaches (Wasserman
+/// and Faust). A vertex that reaches nothing scores 0.
+/// <https://en.wikipedia.org/wiki/Closeness_centrality>
+/// Time complexity: O(|V| (|V| + |E|))
+#[allow(clippy::cast_precision_loss)]
+pub fn closeness<G>(graph: G) -> Vec<f64>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    (0..n)
+        .map(|s| {
+            let (reached, total) = bfs(graph, [s])
+                .skip(1)
+                .fold((0_usize, 0_usize), |(count, sum), (_, d)| {
+                    (count + 1, sum + d)
+                });
+            if total == 0 {
+                0.0
+            } else {
+                let reached = reached as f64;
+                reached / total as f64 * reached / (n - 1) as f64
+            }
+        })
+        .collect()
+}
+
+/// Harmonic centrality.
+///
+/// A vertex scores the sum of the inverse distances to every other vertex,
+/// where unreachable vertices contribute 0.
+/// <https://en.wikipedia.org/wiki/Closeness_centrality#Harmonic_centrality>
+/// Time complexity: O(|V| (|V| + |E|))
+#[allow(clippy::cast_precision_loss)]
+pub fn harmonic<G>(graph: G) -> Vec<f64>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    (0..graph.vertex_count())
+        .map(|s| bfs(graph, [s]).skip(1).map(|(_, d)| 1.0 / d as f64).sum())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{generators::gnp, Graph};
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, b) in actual.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-6, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_pagerank() {
+        let cycle = Graph::from([(0, 1), (1, 2), (2, 0)]);
+        assert_close(&pagerank(&cycle, 0.85, 1e-10, 100), &[1.0 / 3.0; 3]);
+
+        // 1 and 2 link to 0; 0 is dangling and spreads its rank evenly
+        let graph = Graph::from([(1, 0), (2, 0)]);
+        let rank = pagerank(&graph, 0.5, 1e-12, 100);
+        assert_close(&rank, &[0.5, 0.25, 0.25]);
+        assert!((rank.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        // Rounding keeps the change above this tolerance, so the cap stops it
+        let graph = gnp(200, 0.05, 1);
+        let rank = pagerank(&graph, 0.85, 1e-18, 1000);
+        assert!((rank.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(pagerank(&graph, 0.85, 1.0, 0), [1.0 / 200.0; 200]);
+    }
+
+    #[test]
+    fn test_betweenness() {
+        /*
+            0 -> 1 -> 3
+            |         ^
+            '--> 2 ---'
+            and 3 -> 4
+        */
+        let graph = Graph::from([(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)]);
+        assert_close(&betweenness(&graph), &[0.0, 1.0, 1.0, 3.0, 0.0]);
+        assert_close(
+            &weighted_betweenness(&graph, &[1, 1, 1, 5, 1]),
+            &[0.0, 2.0, 0.0, 3.0, 0.0],
+        );
+
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0)]);
+        assert_close(&betweenness(&graph), &[1.0; 3]);
+    }
+
+    #[test]
+    fn test_zero_weight() {
+        /*
+            0 -> 2 (1), 0 -> 1 (1), 1 -> 2 (0), 2 -> 3 (1)
+
+            1 and 2 are both at distance 1 from 0, and 1 is on a shortest path
+            to 2
+        */
+        let graph = Graph::from([(0, 2), (0, 1), (1, 2), (2, 3)]);
+        assert_close(
+            &weighted_betweenness(&graph, &[1, 1, 0, 1]),
+            &[0.0, 1.0, 2.0, 0.0],
+        );
+
+        // With every weight 0, every path is a shortest path
+        let graph = Graph::from([(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)]);
+        assert_close(
+            &weighted_betweenness(&graph, &[0; 5]),
+            &[0.0, 1.0, 1.0, 3.0, 0.0],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "zero-weight cycle")]
+    fn test_zero_weight_cycle() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 1)]);
+        let _ = weighted_betweenness(&graph, &[1, 0, 0]);
+    }
+
+    #[test]
+    fn test_closeness() {
+        // 0 -> 1 -> 2, with 3 isolated
+        let graph = Graph::from([(0, 1), (1, 2), (3, 3)]);
+        assert_close(
+            &closeness(&graph),
+            &[2.0 / 3.0 * 2.0 / 3.0, 1.0 / 3.0, 0.0, 0.0],
+        );
+        assert_close(&harmonic(&graph), &[1.5, 1.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,205 @@
+//! Transitive closure and transitive reduction
+//! <https://en.wikipedia.org/wiki/Transitive_closure#In_graph_theory>
+use super::{scc::condensation, Graph, IntoNeighbors, VertexCount};
+
+impl Graph {
+    /// Returns the transitive closure of the graph. See [`transitive_closure`].
+    #[must_use]
+    pub fn transitive_closure(&self) -> Self {
+        transitive_closure(self)
+    }
+
+    /// Returns the transitive reduction of a DAG. See [`transitive_reduction`].
+    #[must_use]
+    pub fn transitive_reduction(&self) -> Option<Self> {
+        transitive_reduction(self)
+    }
+}
+
+/// A fixed-size set of small integers, packed 64 to a word.
+#[derive(Clone)]
+struct BitSet(Vec<u64>);
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.0[i / 64] >> (i % 64) & 1 != 0
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(&other.0) {
+            *a |= b;
+        }
+    }
+
+    /// Iterates over the members in increasing order.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            (0..64)
+                .filter(move |b| word >> b & 1 != 0)
+                .map(move |b| i * 64 + b)
+        })
+    }
+}
+
+/// Returns the transitive closure of a graph.
+///
+/// The closure has an edge from `u` to `v` whenever there is a non-empty path
+/// from `u` to `v`, so `u` has a self-loop only if it lies on a cycle.
+/// Reachability is computed between strongly connected components with one
+/// bitset per component. Edges are added in order of source, then target.
+/// Time complexity: O(|V| |E| / 64 + |V| + |E'|), where |E'| is the number of
+/// edges in the closure.
+pub fn transitive_closure<G>(graph: G) -> Graph
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    let (dag, component) = condensation(graph);
+    let mut members = vec![vec![]; dag.len()];
+    for (v, &c) in component.iter().enumerate() {
+        members[c].push(v);
+    }
+
+    // Components are in topological order, so successors come later
+    let mut reach = vec![BitSet::new(dag.len()); dag.len()];
+    for c in (0..dag.len()).rev() {
+        for (d, _) in dag.neighbors(c) {
+            let (head, tail) = reach.split_at_mut(d);
+            head[c].union_with(&tail[0]);
+            head[c].insert(d);
+        }
+    }
+    for (u, &c) in component.iter().enumerate() {
+        if members[c].len() > 1 || graph.neighbors(u).any(|(v, _)| v == u) {
+            reach[c].insert(c);
+        }
+    }
+
+    let mut closure = Graph::new(n, 0);
+    for u in 0..n {
+        let mut targets = reach[component[u]]
+            .iter()
+            .flat_map(|d| members[d].iter().copied())
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+        for v in targets {
+            closure.add_edge(u, v);
+        }
+    }
+    closure
+}
+
+/// Returns the transitive reduction of a DAG, or `None` if the graph has a
+/// cycle.
+///
+/// The reduction keeps an edge from `u` to `v` only if there's no other path
+/// from `u` to `v`, so it's the smallest graph with the same reachability.
+/// Parallel edges are merged. Kept edges are added in order of edge id.
+/// <https://en.wikipedia.org/wiki/Transitive_reduction>
+/// Time complexity: O(|V| |E| / 64 + |E| log |E|)
+pub fn transitive_reduction<G>(graph: G) -> Option<Graph>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    let (dag, order) = condensation(graph);
+    if dag.len() != n || (0..n).any(|u| graph.neighbors(u).any(|(v, _)| v == u)) {
+        return None;
+    }
+
+    // `order` maps each vertex to its position in a topological order
+    let mut by_position = vec![0; n];
+    for (v, &i) in order.iter().enumerate() {
+        by_position[i] = v;
+    }
+    let mut descendants = vec![BitSet::new(n); n];
+    let mut kept = vec![];
+    for &u in by_position.iter().rev() {
+        // Visit successors nearest first; a successor already reached through
+        // a nearer one is redundant
+        let mut successors = graph
+            .neighbors(u)
+            .map(|(v, e)| (order[v], e))
+            .collect::<Vec<_>>();
+        successors.sort_unstable();
+        let mut reached = BitSet::new(n);
+        for (i, e) in successors {
+            if reached.contains(i) {
+                continue;
+            }
+            kept.push((e, u, by_position[i]));
+            reached.union_with(&descendants[i]);
+            reached.insert(i);
+        }
+        descendants[order[u]] = reached;
+    }
+
+    kept.sort_unstable();
+    let mut reduction = Graph::new(n, kept.len());
+    for (_, u, v) in kept {
+        reduction.add_edge(u, v);
+    }
+    Some(reduction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure() {
+        /*
+            0 -> 1 <-> 2 -> 3    4
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (2, 1), (2, 3), (4, 4)]);
+        let closure = graph.transitive_closure();
+        let pairs = closure
+            .edges()
+            .into_iter()
+            .map(|(u, v, _)| (u, v))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            [
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (4, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reduction() {
+        /*
+            0 -> 1 -> 2 -> 3, plus the shortcuts 0 -> 2, 0 -> 3 and 1 -> 3
+            and a parallel edge 1 -> 2
+        */
+        let graph = Graph::from([(0, 3), (0, 1), (1, 2), (0, 2), (2, 3), (1, 3), (1, 2)]);
+        let reduction = graph.transitive_reduction().unwrap();
+        assert_eq!(reduction.edges(), [(0, 1, 0), (1, 2, 1), (2, 3, 2)]);
+        assert_eq!(
+            reduction.transitive_closure().edges(),
+            graph.transitive_closure().edges()
+        );
+
+        assert!(Graph::from([(0, 1), (1, 0)])
+            .transitive_reduction()
+            .is_none());
+        assert!(Graph::from([(0, 0)]).transitive_reduction().is_none());
+    }
+}
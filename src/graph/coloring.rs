@@ -0,0 +1,368 @@
+//! Vertex coloring
+//! <https://en.wikipedia.org/wiki/Graph_coloring>
+//!
+//! Colorings treat the graph as undirected: `u` and `v` must get different
+//! colors if there's an edge between them in either direction. Self-loops are
+//! ignored. A coloring maps each vertex to a color in `0..k`.
+use super::{IntoNeighbors, VertexCount};
+use std::collections::{BTreeSet, HashSet};
+
+/// The order in which [`greedy_coloring`] colors vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// In order of vertex id.
+    Natural,
+    /// Highest degree first (Welsh–Powell).
+    LargestFirst,
+    /// Repeatedly remove a vertex of least degree, then color in reverse
+    /// order of removal. Uses at most one more color than the degeneracy.
+    SmallestLast,
+}
+
+/// Returns the sorted, deduplicated neighbors of each vertex, ignoring edge
+/// direction and self-loops.
+fn undirected<G: IntoNeighbors + VertexCount>(graph: G) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![vec![]; graph.vertex_count()];
+    for u in 0..graph.vertex_count() {
+        for (v, _) in graph.neighbors(u).filter(|&(v, _)| v != u) {
+            adjacency[u].push(v);
+            adjacency[v].push(u);
+        }
+    }
+    for list in &mut adjacency {
+        list.sort_unstable();
+        list.dedup();
+    }
+    adjacency
+}
+
+/// Returns the order of removal when repeatedly removing a vertex of least
+/// remaining degree, with ties broken by vertex id.
+fn degeneracy_order(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let mut degree = adjacency.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut queue = degree
+        .iter()
+        .enumerate()
+        .map(|(v, &d)| (d, v))
+        .collect::<BTreeSet<_>>();
+    let mut order = Vec::with_capacity(adjacency.len());
+    while let Some((_, u)) = queue.pop_first() {
+        order.push(u);
+        for &v in &adjacency[u] {
+            if queue.remove(&(degree[v], v)) {
+                degree[v] -= 1;
+                queue.insert((degree[v], v));
+            }
+        }
+    }
+    order
+}
+
+/// Colors vertices one at a time in the given `order`, each with the smallest
+/// color not used by its neighbors.
+/// Time complexity: O(|V| log |V| + |E| log |E|)
+pub fn greedy_coloring<G>(graph: G, order: Order) -> Vec<usize>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let adjacency = undirected(graph);
+    let vertices = match order {
+        Order::Natural => (0..adjacency.len()).collect(),
+        Order::LargestFirst => {
+            let mut vertices = (0..adjacency.len()).collect::<Vec<_>>();
+            vertices.sort_by_key(|&v| std::cmp::Reverse(adjacency[v].len()));
+            vertices
+        }
+        Order::SmallestLast => {
+            let mut vertices = degeneracy_order(&adjacency);
+            vertices.reverse();
+            vertices
+        }
+    };
+
+    let mut colors = vec![None; adjacency.len()];
+    let mut taken = vec![false; adjacency.len() + 1];
+    for u in vertices {
+        let neighbor_colors = adjacency[u]
+            .iter()
+            .filter_map(|&v| colors[v])
+            .collect::<Vec<_>>();
+        for &c in &neighbor_colors {
+            taken[c] = true;
+        }
+        colors[u] = taken.iter().position(|&t| !t);
+        for c in neighbor_colors {
+            taken[c] = false;
+        }
+    }
+    colors.into_iter().flatten().collect()
+}
+
+/// Brélaz's `DSatur` heuristic.
+///
+/// Repeatedly colors the vertex whose neighbors already use the most distinct
+/// colors, breaking ties by degree and then by vertex id, with the smallest
+/// color available.
+/// <https://en.wikipedia.org/wiki/DSatur>
+/// Time complexity: O((|V| + |E|) log |V|)
+pub fn dsatur<G>(graph: G) -> Vec<usize>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let adjacency = undirected(graph);
+    let n = adjacency.len();
+    let mut neighbor_colors = vec![HashSet::new(); n];
+    let key = |v: usize, seen: &HashSet<usize>| {
+        (
+            std::cmp::Reverse(seen.len()),
+            std::cmp::Reverse(adjacency[v].len()),
+            v,
+        )
+    };
+    let mut queue = (0..n)
+        .map(|v| key(v, &neighbor_colors[v]))
+        .collect::<BTreeSet<_>>();
+    let mut colors = vec![0; n];
+    while let Some((.., u)) = queue.pop_first() {
+        let seen = &neighbor_colors[u];
+        let c = (0..=seen.len())
+            .find(|c| !seen.contains(c))
+            .unwrap_or_default();
+        colors[u] = c;
+        for &v in &adjacency[u] {
+            if queue.contains(&key(v, &neighbor_colors[v])) && !neighbor_colors[v].contains(&c) {
+                queue.remove(&key(v, &neighbor_colors[v]));
+                neighbor_colors[v].insert(c);
+                queue.insert(key(v, &neighbor_colors[v]));
+            }
+        }
+    }
+    colors
+}
+
+/// Search state for [`exact_coloring`].
+struct Search<'a> {
+    /// Sorted neighbors of each vertex.
+    adjacency: &'a [Vec<usize>],
+    /// The color of each vertex, if colored yet.
+    colors: Vec<Option<usize>>,
+    /// `counts[v][c]` is the number of neighbors of `v` with color `c`.
+    counts: Vec<Vec<usize>>,
+    /// The number of distinct colors in `counts[v]`.
+    saturation: Vec<usize>,
+    /// The best complete coloring found so far.
+    best: Vec<usize>,
+    /// The number of colors `best` uses.
+    best_count: usize,
+}
+
+impl Search<'_> {
+    /// Gives `u` color `c`, or takes it away.
+    fn set(&mut self, u: usize, c: usize, on: bool) {
+        self.colors[u] = on.then_some(c);
+        let adjacency = self.adjacency;
+        for &v in &adjacency[u] {
+            let count = &mut self.counts[v][c];
+            if on {
+                *count += 1;
+                self.saturation[v] += usize::from(*count == 1);
+            } else {
+                *count -= 1;
+                self.saturation[v] -= usize::from(*count == 0);
+            }
+        }
+    }
+
+    /// Extends the partial coloring, which uses colors `0..used`, in every way
+    /// that could beat the best coloring so far.
+    fn extend(&mut self, used: usize) {
+        let uncolored = (0..self.colors.len()).filter(|&v| self.colors[v].is_none());
+        let Some(u) = uncolored.max_by_key(|&v| {
+            (
+                self.saturation[v],
+                self.adjacency[v].len(),
+                std::cmp::Reverse(v),
+            )
+        }) else {
+            self.best = self.colors.iter().flatten().copied().collect();
+            self.best_count = used;
+            return;
+        };
+        // A new color is only worth trying if it still beats the best
+        let limit = (used + 1).min(self.best_count - 1);
+        for c in 0..limit {
+            if self.counts[u][c] == 0 {
+                self.set(u, c, true);
+                self.extend(used.max(c + 1));
+                self.set(u, c, false);
+            }
+        }
+    }
+}
+
+/// Returns a coloring with as few colors as possible.
+///
+/// Runs a branch and bound search in `DSatur` order, starting from the
+/// [`dsatur`] coloring as an upper bound. Exponential time, so it's only practical for
+/// small graphs.
+pub fn exact_coloring<G>(graph: G) -> Vec<usize>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let adjacency = undirected(graph);
+    let n = adjacency.len();
+    let best = dsatur(graph);
+    let best_count = best.iter().max().map_or(0, |&c| c + 1);
+    let mut search = Search {
+        adjacency: &adjacency,
+        colors: vec![None; n],
+        counts: vec![vec![0; best_count]; n],
+        saturation: vec![0; n],
+        best,
+        best_count,
+    };
+    search.extend(0);
+    search.best
+}
+
+/// Returns an edge whose endpoints have the same color, or `None` if
+/// `colors` is a proper coloring.
+///
+/// # Panics
+///
+/// Panics if `colors.len() != graph.vertex_count()`.
+pub fn conflicting_edge<G>(graph: G, colors: &[usize]) -> Option<usize>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    assert_eq!(colors.len(), graph.vertex_count());
+    (0..graph.vertex_count()).find_map(|u| {
+        graph
+            .neighbors(u)
+            .find(|&(v, _)| v != u && colors[u] == colors[v])
+            .map(|(_, e)| e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{
+        generators::{complete, gnp},
+        Graph,
+    };
+
+    /// Returns the number of colors used.
+    fn count(colors: &[usize]) -> usize {
+        colors.iter().max().map_or(0, |&c| c + 1)
+    }
+
+    #[test]
+    fn test_heuristics() {
+        /*
+            a crown: 2i and 2j + 1 are adjacent unless i == j, so coloring in
+            natural order needs a color per pair, though two colors suffice
+        */
+        let mut crown = Graph::new(8, 12);
+        for i in 0..4 {
+            for j in (0..4).filter(|&j| j != i) {
+                crown.add_edge(2 * i, 2 * j + 1);
+            }
+        }
+        assert_eq!(
+            greedy_coloring(&crown, Order::Natural),
+            [0, 0, 1, 1, 2, 2, 3, 3]
+        );
+        assert_eq!(dsatur(&crown), [0, 1, 0, 1, 0, 1, 0, 1]);
+        for colors in [
+            greedy_coloring(&crown, Order::LargestFirst),
+            greedy_coloring(&crown, Order::SmallestLast),
+        ] {
+            assert_eq!(conflicting_edge(&crown, &colors), None);
+        }
+
+        // a star with long arms: largest-first and smallest-last both color
+        // the center before its arms fight over colors
+        let graph = Graph::from([(0, 1), (0, 2), (0, 3), (1, 4), (2, 5), (3, 6)]);
+        assert_eq!(count(&greedy_coloring(&graph, Order::LargestFirst)), 2);
+        assert_eq!(count(&greedy_coloring(&graph, Order::SmallestLast)), 2);
+
+        // an odd cycle needs three colors
+        let cycle = Graph::from([(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert_eq!(count(&dsatur(&cycle)), 3);
+        assert_eq!(conflicting_edge(&cycle, &[0, 1, 0, 1, 0]), Some(4));
+    }
+
+    /// Returns the chromatic number by trying every coloring with 1, 2, ...
+    /// colors.
+    fn chromatic_number(graph: &Graph) -> usize {
+        let edges = graph.edges();
+        let n = graph.len();
+        (1..=n)
+            .find(|&k| {
+                (0..k.pow(u32::try_from(n).unwrap())).any(|mut code| {
+                    let colors = (0..n)
+                        .map(|_| {
+                            let c = code % k;
+                            code /= k;
+                            c
+                        })
+                        .collect::<Vec<_>>();
+                    edges
+                        .iter()
+                        .all(|&(u, v, _)| u == v || colors[u] != colors[v])
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_exact() {
+        // the Petersen graph has chromatic number 3
+        let mut petersen = Graph::new(10, 15);
+        for i in 0..5 {
+            petersen.add_edge(i, (i + 1) % 5);
+            petersen.add_edge(i, i + 5);
+            petersen.add_edge(i + 5, (i + 2) % 5 + 5);
+        }
+        let colors = exact_coloring(&petersen);
+        assert_eq!(conflicting_edge(&petersen, &colors), None);
+        assert_eq!(count(&colors), 3);
+
+        for n in 1..7 {
+            assert_eq!(count(&exact_coloring(&complete(n))), n);
+        }
+        let odd = Graph::from([(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 0)]);
+        assert_eq!(count(&exact_coloring(&odd)), 3);
+        let even = Graph::from([(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        assert_eq!(count(&exact_coloring(&even)), 2);
+        assert_eq!(count(&exact_coloring(&Graph::new(3, 0))), 1);
+        assert!(exact_coloring(&Graph::new(0, 0)).is_empty());
+
+        // DSatur's greedy choices need four colors here, but three suffice
+        let graph = Graph::from([
+            (0, 2),
+            (0, 3),
+            (0, 5),
+            (1, 2),
+            (1, 3),
+            (1, 6),
+            (1, 7),
+            (4, 3),
+            (5, 6),
+            (5, 7),
+            (7, 6),
+        ]);
+        assert_eq!(count(&dsatur(&graph)), 4);
+        let colors = exact_coloring(&graph);
+        assert_eq!(conflicting_edge(&graph, &colors), None);
+        assert_eq!(count(&colors), 3);
+
+        for seed in 0..10 {
+            let graph = gnp(7, 0.4, seed);
+            let colors = exact_coloring(&graph);
+            assert_eq!(conflicting_edge(&graph, &colors), None);
+            assert_eq!(count(&colors), chromatic_number(&graph));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Compressed sparse row (CSR) graph representation
+//! <https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_row_(CSR,_CRS_or_Yale_format)>
+use super::{
+    dijkstra::dijkstra,
+    traversal::{
+        level_order, post_order, pre_order, LevelOrderIterator, PostOrderIterator, PreOrderIterator,
+    },
+    EdgeCount, Graph, IntoNeighbors, VertexCount,
+};
+use std::{iter::Copied, slice::Iter};
+
+/// An immutable directed graph whose adjacency lists are stored contiguously.
+///
+/// Neighbors are returned in insertion order, and edges keep the ids they had
+/// in the [`Graph`] they were frozen from, so edge weights can be shared.
+/// Space: O(|V| + |E|)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph {
+    /// Vertex `v`'s adjacency list is `adjacency[offsets[v]..offsets[v + 1]]`.
+    offsets: Vec<usize>,
+    /// `(vertex, edge)` pairs of every adjacency list, back to back.
+    adjacency: Vec<(usize, usize)>,
+}
+
+/// An iterator over a vertex's `(vertex, edge)` pairs in a [`CsrGraph`].
+pub type CsrNeighbors<'a> = Copied<Iter<'a, (usize, usize)>>;
+
+impl Graph {
+    /// Returns an immutable copy of the graph in compressed sparse row form.
+    /// Time complexity: O(|V| + |E|)
+    #[must_use]
+    pub fn freeze(&self) -> CsrGraph {
+        let mut offsets = vec![0; self.len() + 1];
+        for u in 0..self.len() {
+            offsets[u + 1] = offsets[u] + self.neighbors(u).count();
+        }
+        // Adjacency lists are linked newest-first, so fill each slot from the back
+        let mut adjacency = vec![(0, 0); self.edge_count()];
+        for u in 0..self.len() {
+            for (i, pair) in (offsets[u]..offsets[u + 1]).rev().zip(self.neighbors(u)) {
+                adjacency[i] = pair;
+            }
+        }
+        CsrGraph { offsets, adjacency }
+    }
+}
+
+impl CsrGraph {
+    /// Returns the number of vertices in the graph.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns true if the graph has no vertices.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of edges in the graph.
+    #[must_use]
+    pub const fn edge_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Gets vertex `node`'s outgoing `(vertex, edge)` pairs in insertion order.
+    #[must_use]
+    pub fn neighbor_slice(&self, node: usize) -> &[(usize, usize)] {
+        &self.adjacency[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// Gets vertex `node`'s neighbors in insertion order.
+    pub fn neighbors(&self, node: usize) -> CsrNeighbors<'_> {
+        self.neighbor_slice(node).iter().copied()
+    }
+
+    /// Same as [`Graph::pre_order`].
+    #[must_use]
+    pub fn pre_order(&self, start: usize) -> PreOrderIterator<&Self> {
+        pre_order(self, start)
+    }
+
+    /// Same as [`Graph::level_order`].
+    #[must_use]
+    pub fn level_order(&self, start: usize) -> LevelOrderIterator<&Self> {
+        level_order(self, start)
+    }
+
+    /// Same as [`Graph::post_order`].
+    #[must_use]
+    pub fn post_order(&self, start: usize) -> PostOrderIterator<&Self> {
+        post_order(self, start)
+    }
+
+    /// Same as [`Graph::dijkstra`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`.
+    #[must_use]
+    pub fn dijkstra(&self, weights: &[u64], start: usize) -> Vec<u64> {
+        dijkstra(self, weights, start)
+    }
+}
+
+impl VertexCount for CsrGraph {
+    fn vertex_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EdgeCount for CsrGraph {
+    fn edge_count(&self) -> usize {
+        self.adjacency.len()
+    }
+}
+
+impl<'a> IntoNeighbors for &'a CsrGraph {
+    type Neighbors = CsrNeighbors<'a>;
+
+    fn neighbors(self, node: usize) -> Self::Neighbors {
+        CsrGraph::neighbors(self, node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze() {
+        let graph = Graph::from([(2, 3), (2, 4), (4, 1), (1, 2)]).freeze();
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.neighbor_slice(2), [(3, 0), (4, 1)]);
+        assert_eq!(graph.neighbors(4).collect::<Vec<_>>(), [(1, 2)]);
+        assert!(graph.neighbor_slice(0).is_empty());
+    }
+
+    #[test]
+    fn test_algorithms() {
+        let graph = Graph::from([(1, 2), (1, 3), (2, 4), (3, 5), (3, 6), (5, 7), (5, 8)]).freeze();
+        assert_eq!(
+            graph.pre_order(1).collect::<Vec<_>>(),
+            [(2, 0), (4, 2), (3, 1), (5, 3), (7, 5), (8, 6), (6, 4)]
+        );
+        assert_eq!(
+            graph.level_order(1).collect::<Vec<_>>(),
+            [(2, 0), (3, 1), (4, 2), (5, 3), (6, 4), (7, 5), (8, 6)]
+        );
+        assert_eq!(
+            graph.post_order(1).collect::<Vec<_>>(),
+            [4, 2, 7, 8, 5, 6, 3, 1]
+        );
+
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0)]).freeze();
+        assert_eq!(graph.dijkstra(&[7, 3, 5], 0), [0, 7, 10]);
+    }
+}
@@ -1,25 +1,241 @@
-use super::Graph;
+use super::{
+    dfs::{dfs_visit, Control, DfsEvent},
+    scc::strongly_connected_components,
+    Graph, IntoNeighbors, VertexCount,
+};
 
 impl Graph {
     /// Returns true if the graph is a directed acyclic graph.
     #[must_use]
     pub fn is_dag(&self) -> bool {
-        let mut visited = vec![false; self.len()];
-        let mut stack = vec![];
-        for v in 0..self.len() {
-            if !visited[v] {
-                stack.push(v);
-                while let Some(v) = stack.pop() {
-                    if visited[v] {
-                        return false;
+        is_dag(self)
+    }
+
+    /// Returns an iterator over the elementary cycles. See [`elementary_cycles`].
+    #[must_use]
+    pub fn elementary_cycles(&self) -> ElementaryCycles {
+        elementary_cycles(self)
+    }
+}
+
+/// Returns true if any graph is a directed acyclic graph. See [`Graph::is_dag`].
+#[must_use]
+pub fn is_dag<G: IntoNeighbors + VertexCount>(graph: G) -> bool {
+    let control = dfs_visit(graph, 0..graph.vertex_count(), |event| match event {
+        DfsEvent::BackEdge(..) => Control::Break,
+        _ => Control::Continue,
+    });
+    control == Control::Continue
+}
+
+/// Returns an iterator over the elementary cycles of a graph, that is, the
+/// cycles that don't repeat a vertex. See [`ElementaryCycles`].
+#[must_use]
+pub fn elementary_cycles<G>(graph: G) -> ElementaryCycles
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    ElementaryCycles {
+        adjacency: (0..n).map(|u| graph.neighbors(u).collect()).collect(),
+        max_length: None,
+        start: 0,
+        in_component: vec![false; n],
+        blocked: vec![false; n],
+        blocking: vec![vec![]; n],
+        stack: vec![],
+        path: vec![],
+    }
+}
+
+/// One vertex on the current path of the search.
+struct Frame {
+    /// The vertex.
+    node: usize,
+    /// The index of its next outgoing edge to try.
+    next: usize,
+    /// True if a cycle was found through the vertex.
+    closed: bool,
+}
+
+/// Iterator over the elementary cycles of a graph, using Johnson's algorithm.
+/// <https://doi.org/10.1137/0204007>
+///
+/// Each cycle is given by its edges, starting from its least vertex. Cycles
+/// through parallel edges are reported separately, and a self-loop is a cycle
+/// of length 1. Cycles are produced in order of their least vertex.
+/// Time complexity: O((|V| + |E|)(c + 1)) for c cycles, plus O(|V| (|V| + |E|))
+/// to find the strongly connected components
+pub struct ElementaryCycles {
+    /// The outgoing `(vertex, edge)` pairs of each vertex.
+    adjacency: Vec<Vec<(usize, usize)>>,
+    /// The length of the longest cycle to report.
+    max_length: Option<usize>,
+    /// The least vertex of the cycles being searched for.
+    start: usize,
+    /// True for the vertices in the strongly connected component of `start`,
+    /// among the vertices no less than `start`.
+    in_component: Vec<bool>,
+    /// True for the vertices that can't currently lead back to `start`.
+    blocked: Vec<bool>,
+    /// Maps a vertex to the blocked vertices to unblock along with it.
+    blocking: Vec<Vec<usize>>,
+    /// The current path from `start`.
+    stack: Vec<Frame>,
+    /// The edges of the current path from `start`.
+    path: Vec<usize>,
+}
+
+impl ElementaryCycles {
+    /// Only reports cycles of at most `max_length` edges.
+    ///
+    /// Johnson's blocking can't be used with a length limit, so the search
+    /// backtracks over every short enough path instead.
+    #[must_use]
+    pub const fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Moves on to the next start vertex whose component has a cycle, and
+    /// returns false if there's none.
+    fn next_start(&mut self) -> bool {
+        let n = self.adjacency.len();
+        while self.start < n {
+            let s = self.start;
+            self.start += 1;
+            let mut graph = Graph::new(n, 0);
+            for u in s..n {
+                for &(v, _) in self.adjacency[u].iter().filter(|&&(v, _)| v >= s) {
+                    graph.add_edge(u, v);
+                }
+            }
+            let components = strongly_connected_components(&graph);
+            let Some(component) = components.iter().find(|c| c.contains(&s)) else {
+                continue;
+            };
+            if component.len() == 1 && !self.adjacency[s].iter().any(|&(v, _)| v == s) {
+                continue;
+            }
+            self.in_component.fill(false);
+            for &v in component {
+                self.in_component[v] = true;
+                self.blocked[v] = false;
+                self.blocking[v].clear();
+            }
+            self.blocked[s] = true;
+            self.stack.push(Frame {
+                node: s,
+                next: 0,
+                closed: false,
+            });
+            return true;
+        }
+        false
+    }
+
+    /// Unblocks `node`, and everything waiting on it.
+    fn unblock(&mut self, node: usize) {
+        let mut pending = vec![node];
+        while let Some(u) = pending.pop() {
+            if self.blocked[u] {
+                self.blocked[u] = false;
+                pending.append(&mut self.blocking[u]);
+            }
+        }
+    }
+}
+
+impl Iterator for ElementaryCycles {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(frame) = self.stack.last_mut() else {
+                if self.next_start() {
+                    continue;
+                }
+                return None;
+            };
+            let (u, s) = (frame.node, self.start - 1);
+            let room = self
+                .max_length
+                .map_or(usize::MAX, |max| max.saturating_sub(self.path.len()));
+            if let Some(&(v, e)) = self.adjacency[u].get(frame.next) {
+                frame.next += 1;
+                if !self.in_component[v] {
+                    continue;
+                }
+                if v == s {
+                    if room >= 1 {
+                        frame.closed = true;
+                        let mut cycle = self.path.clone();
+                        cycle.push(e);
+                        return Some(cycle);
                     }
-                    visited[v] = true;
-                    for (w, _) in self.neighbors(v) {
-                        stack.push(w);
+                } else if !self.blocked[v] && room >= 2 {
+                    self.blocked[v] = true;
+                    self.path.push(e);
+                    self.stack.push(Frame {
+                        node: v,
+                        next: 0,
+                        closed: false,
+                    });
+                }
+                continue;
+            }
+
+            let closed = frame.closed;
+            self.stack.pop();
+            if closed || self.max_length.is_some() {
+                self.unblock(u);
+            } else {
+                for i in 0..self.adjacency[u].len() {
+                    let v = self.adjacency[u][i].0;
+                    if self.in_component[v] && !self.blocking[v].contains(&u) {
+                        self.blocking[v].push(u);
                     }
                 }
             }
+            if let Some(parent) = self.stack.last_mut() {
+                parent.closed |= closed;
+                self.path.pop();
+            }
         }
-        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_dag() {
+        assert!(Graph::from([(0, 1), (0, 2), (1, 3), (2, 3)]).is_dag());
+        assert!(!Graph::from([(0, 1), (1, 2), (2, 0)]).is_dag());
+        assert!(!Graph::from([(0, 1), (1, 1)]).is_dag());
+        assert!(Graph::new(3, 0).is_dag());
+    }
+
+    #[test]
+    fn test_elementary_cycles() {
+        /*
+            0 -> 1 -> 2 -> 0, 1 -> 0, 2 -> 2, and 2 -> 3 -> 1
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (1, 0), (2, 2), (2, 3), (3, 1)]);
+        let mut cycles = elementary_cycles(&graph).collect::<Vec<_>>();
+        cycles.sort();
+        assert_eq!(cycles, [vec![0, 1, 2], vec![0, 3], vec![1, 5, 6], vec![4]]);
+
+        let mut short = graph.elementary_cycles().max_length(2).collect::<Vec<_>>();
+        short.sort();
+        assert_eq!(short, [vec![0, 3], vec![4]]);
+
+        assert_eq!(elementary_cycles(&Graph::from([(0, 1), (1, 2)])).count(), 0);
+
+        // a complete graph on n vertices has sum C(n, k) (k - 1)! cycles
+        let complete = crate::graph::generators::complete(5);
+        assert_eq!(elementary_cycles(&complete).count(), 10 + 20 + 30 + 24);
+        assert_eq!(elementary_cycles(&complete).max_length(3).count(), 30);
     }
 }
@@ -0,0 +1,209 @@
+//! Shortest and longest paths in directed acyclic graphs
+//! <https://en.wikipedia.org/wiki/Longest_path_problem#Acyclic_graphs>
+//!
+//! Edges are relaxed once each, in topological order, so weights may be
+//! negative. All functions return `None` if the graph has a cycle.
+use super::{topological_sort::kahns_algorithm, EdgeCount, Graph, IntoNeighbors, VertexCount};
+
+impl Graph {
+    /// Single-source shortest paths on a DAG. See [`dag_shortest_paths`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`, or if a path weight
+    /// overflows `i64`.
+    #[must_use]
+    pub fn dag_shortest_paths(&self, weights: &[i64], start: usize) -> Option<DagPaths> {
+        dag_shortest_paths(self, weights, start)
+    }
+
+    /// Single-source longest paths on a DAG. See [`dag_longest_paths`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`, or if a path weight
+    /// overflows `i64`.
+    #[must_use]
+    pub fn dag_longest_paths(&self, weights: &[i64], start: usize) -> Option<DagPaths> {
+        dag_longest_paths(self, weights, start)
+    }
+
+    /// Returns the weight and edges of a longest path in a DAG. See
+    /// [`critical_path`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`, or if a path weight
+    /// overflows `i64`.
+    #[must_use]
+    pub fn critical_path(&self, weights: &[i64]) -> Option<(i64, Vec<usize>)> {
+        critical_path(self, weights)
+    }
+}
+
+/// Path weights and a path tree found by relaxing the edges of a DAG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DagPaths {
+    /// Maps a vertex to the weight of its best path, if reachable.
+    pub distance: Vec<Option<i64>>,
+    /// Maps a vertex to the `(vertex, edge)` its best path arrives from.
+    /// Starts and unreachable vertices have no parent.
+    pub parent: Vec<Option<(usize, usize)>>,
+}
+
+impl DagPaths {
+    /// Returns the edges of the best path to `node`, if reachable.
+    #[must_use]
+    pub fn path(&self, mut node: usize) -> Option<Vec<usize>> {
+        self.distance[node]?;
+        let mut path = vec![];
+        while let Some((u, e)) = self.parent[node] {
+            path.push(e);
+            node = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Relaxes every edge in topological order, replacing a vertex's distance
+/// whenever `better(new, old)` holds.
+fn relax<G>(
+    graph: G,
+    weights: &[i64],
+    mut distance: Vec<Option<i64>>,
+    better: fn(i64, i64) -> bool,
+) -> Option<DagPaths>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    let mut parent = vec![None; graph.vertex_count()];
+    for u in kahns_algorithm(graph)? {
+        let Some(dist_u) = distance[u] else {
+            continue;
+        };
+        for (v, e) in graph.neighbors(u) {
+            let alt = dist_u
+                .checked_add(weights[e])
+                .expect("path weight overflows i64");
+            if distance[v].is_none_or(|dist_v| better(alt, dist_v)) {
+                distance[v] = Some(alt);
+                parent[v] = Some((u, e));
+            }
+        }
+    }
+    Some(DagPaths { distance, parent })
+}
+
+/// Single-source shortest paths on a DAG, with any edge weights.
+/// Returns `None` if the graph has a cycle.
+/// Time complexity: O(|V| + |E|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`, or if a path weight
+/// overflows `i64`.
+pub fn dag_shortest_paths<G>(graph: G, weights: &[i64], start: usize) -> Option<DagPaths>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    let mut distance = vec![None; graph.vertex_count()];
+    distance[start] = Some(0);
+    relax(graph, weights, distance, |new, old| new < old)
+}
+
+/// Single-source longest paths on a DAG, with any edge weights.
+/// Returns `None` if the graph has a cycle.
+/// Time complexity: O(|V| + |E|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`, or if a path weight
+/// overflows `i64`.
+pub fn dag_longest_paths<G>(graph: G, weights: &[i64], start: usize) -> Option<DagPaths>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    let mut distance = vec![None; graph.vertex_count()];
+    distance[start] = Some(0);
+    relax(graph, weights, distance, |new, old| new > old)
+}
+
+/// Returns the weight and edges of a heaviest path in a DAG.
+///
+/// The path may start and end anywhere. With activities as edges weighted by
+/// their durations, this is the critical path of a project schedule.
+/// <https://en.wikipedia.org/wiki/Critical_path_method>
+/// Returns `None` if the graph has a cycle.
+/// Time complexity: O(|V| + |E|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`, or if a path weight
+/// overflows `i64`.
+pub fn critical_path<G>(graph: G, weights: &[i64]) -> Option<(i64, Vec<usize>)>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    // Every vertex starts an empty path of weight 0
+    let paths = relax(
+        graph,
+        weights,
+        vec![Some(0); graph.vertex_count()],
+        |new, old| new > old,
+    )?;
+    let Some((end, weight)) = paths
+        .distance
+        .iter()
+        .enumerate()
+        .filter_map(|(v, &d)| Some((v, d?)))
+        .max_by_key(|&(v, d)| (d, std::cmp::Reverse(v)))
+    else {
+        return Some((0, vec![]));
+    };
+    Some((weight, paths.path(end)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        /*
+            0 -> 1 -> 3
+            |         ^
+            '--> 2 ---'
+        */
+        let graph = Graph::from([(0, 1), (1, 3), (0, 2), (2, 3)]);
+        let weights = [2, 5, 1, -3];
+
+        let shortest = graph.dag_shortest_paths(&weights, 0).unwrap();
+        assert_eq!(shortest.distance, [Some(0), Some(2), Some(1), Some(-2)]);
+        assert_eq!(shortest.path(3), Some(vec![2, 3]));
+
+        let longest = graph.dag_longest_paths(&weights, 0).unwrap();
+        assert_eq!(longest.distance, [Some(0), Some(2), Some(1), Some(7)]);
+        assert_eq!(longest.path(3), Some(vec![0, 1]));
+
+        let from_2 = graph.dag_longest_paths(&weights, 2).unwrap();
+        assert_eq!(from_2.distance, [None, None, Some(0), Some(-3)]);
+        assert_eq!(from_2.path(0), None);
+
+        assert_eq!(graph.critical_path(&weights), Some((7, vec![0, 1])));
+        assert_eq!(graph.critical_path(&[-1; 4]), Some((0, vec![])));
+
+        let cyclic = Graph::from([(0, 1), (1, 0)]);
+        assert_eq!(cyclic.dag_shortest_paths(&[1, 1], 0), None);
+        assert_eq!(cyclic.critical_path(&[1, 1]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "path weight overflows i64")]
+    fn test_overflow() {
+        let graph = Graph::from([(0, 1), (1, 2)]);
+        let _ = graph.dag_longest_paths(&[i64::MAX, 1], 0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Event-based depth-first search
+//! <https://en.wikipedia.org/wiki/Depth-first_search#Output_of_a_depth-first_search>
+use super::{traversal::TraversalState, IntoNeighbors, VertexCount};
+
+/// An event emitted by [`dfs_visit`]. Times count discoveries and finishes
+/// together, starting from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsEvent {
+    /// A vertex was reached for the first time, at the given time.
+    Discover(usize, usize),
+    /// An edge `(from, to, edge)` that leads to an undiscovered vertex.
+    TreeEdge(usize, usize, usize),
+    /// An edge `(from, to, edge)` that leads to an ancestor that hasn't finished.
+    BackEdge(usize, usize, usize),
+    /// An edge `(from, to, edge)` that leads to an already finished vertex.
+    ForwardOrCrossEdge(usize, usize, usize),
+    /// All of a vertex's descendants were finished, at the given time.
+    Finish(usize, usize),
+}
+
+/// What [`dfs_visit`] should do after an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Keep searching.
+    Continue,
+    /// On `Discover`, skip the vertex's outgoing edges. On `TreeEdge`, don't
+    /// follow the edge. Same as `Continue` for other events.
+    Prune,
+    /// Stop the search immediately.
+    Break,
+}
+
+/// The discovered and finished marks of [`dfs_visit_with`], which can be
+/// reused across searches.
+#[derive(Debug, Clone, Default)]
+pub struct DfsState {
+    /// marks the discovered nodes
+    discovered: TraversalState,
+    /// marks the finished nodes
+    finished: TraversalState,
+}
+
+impl DfsState {
+    /// Creates a state for graphs with up to `len` vertices. It grows as needed.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            discovered: TraversalState::new(len),
+            finished: TraversalState::new(len),
+        }
+    }
+}
+
+/// Runs a depth-first search, calling `visitor` on every event.
+///
+/// A new search tree is started from each of `starts` that hasn't been
+/// discovered yet. Returns [`Control::Break`] if the visitor stopped the search.
+/// Time complexity: O(|V| + |E|)
+pub fn dfs_visit<G, I, F>(graph: G, starts: I, visitor: F) -> Control
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    F: FnMut(DfsEvent) -> Control,
+{
+    dfs_visit_with(
+        graph,
+        starts,
+        &mut DfsState::new(graph.vertex_count()),
+        visitor,
+    )
+}
+
+/// Same as [`dfs_visit`], but keeps its marks in `state`, which is reset
+/// first. Only the vertices the search reaches cost anything.
+pub fn dfs_visit_with<G, I, F>(graph: G, starts: I, state: &mut DfsState, mut visitor: F) -> Control
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    F: FnMut(DfsEvent) -> Control,
+{
+    let DfsState {
+        discovered,
+        finished,
+    } = state;
+    discovered.prepare(graph.vertex_count());
+    finished.prepare(graph.vertex_count());
+    let mut time = 0;
+    let mut stack = vec![];
+
+    for start in starts {
+        if !discovered.visit(start) {
+            continue;
+        }
+        match visitor(DfsEvent::Discover(start, time)) {
+            Control::Break => return Control::Break,
+            Control::Prune => stack.push((start, None)),
+            Control::Continue => stack.push((start, Some(graph.neighbors(start)))),
+        }
+        time += 1;
+
+        while let Some((u, neighbors)) = stack.last_mut() {
+            let u = *u;
+            let Some((v, e)) = neighbors.as_mut().and_then(Iterator::next) else {
+                stack.pop();
+                finished.visit(u);
+                if visitor(DfsEvent::Finish(u, time)) == Control::Break {
+                    return Control::Break;
+                }
+                time += 1;
+                continue;
+            };
+
+            let control = if !discovered.is_visited(v) {
+                match visitor(DfsEvent::TreeEdge(u, v, e)) {
+                    Control::Continue => {
+                        discovered.visit(v);
+                        let control = visitor(DfsEvent::Discover(v, time));
+                        time += 1;
+                        let neighbors = (control == Control::Continue).then(|| graph.neighbors(v));
+                        stack.push((v, neighbors));
+                        control
+                    }
+                    control => control,
+                }
+            } else if finished.is_visited(v) {
+                visitor(DfsEvent::ForwardOrCrossEdge(u, v, e))
+            } else {
+                visitor(DfsEvent::BackEdge(u, v, e))
+            };
+            if control == Control::Break {
+                return Control::Break;
+            }
+        }
+    }
+    Control::Continue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_events() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (0, 2), (3, 2)]);
+        let mut events = vec![];
+        dfs_visit(&graph, 0..graph.len(), |event| {
+            events.push(event);
+            Control::Continue
+        });
+        assert_eq!(
+            events,
+            [
+                DfsEvent::Discover(0, 0),
+                DfsEvent::TreeEdge(0, 2, 3),
+                DfsEvent::Discover(2, 1),
+                DfsEvent::BackEdge(2, 0, 2),
+                DfsEvent::Finish(2, 2),
+                DfsEvent::TreeEdge(0, 1, 0),
+                DfsEvent::Discover(1, 3),
+                DfsEvent::ForwardOrCrossEdge(1, 2, 1),
+                DfsEvent::Finish(1, 4),
+                DfsEvent::Finish(0, 5),
+                DfsEvent::Discover(3, 6),
+                DfsEvent::ForwardOrCrossEdge(3, 2, 4),
+                DfsEvent::Finish(3, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reused_state() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (0, 2), (3, 2)]);
+        let mut state = DfsState::default();
+        for starts in [vec![0, 1, 2, 3], vec![3], vec![1, 0]] {
+            let (mut expected, mut events) = (vec![], vec![]);
+            dfs_visit(&graph, starts.clone(), |event| {
+                expected.push(event);
+                Control::Continue
+            });
+            dfs_visit_with(&graph, starts, &mut state, |event| {
+                events.push(event);
+                Control::Continue
+            });
+            assert_eq!(events, expected);
+        }
+    }
+
+    #[test]
+    fn test_control() {
+        let graph = Graph::from([(0, 1), (1, 2), (0, 3)]);
+        let mut discovered = vec![];
+        let control = dfs_visit(&graph, [0], |event| match event {
+            DfsEvent::Discover(1, _) => Control::Prune,
+            DfsEvent::Discover(v, _) => {
+                discovered.push(v);
+                Control::Continue
+            }
+            _ => Control::Continue,
+        });
+        assert_eq!(control, Control::Continue);
+        assert_eq!(discovered, [0, 3]);
+
+        let mut finished = vec![];
+        let control = dfs_visit(&graph, [0], |event| match event {
+            DfsEvent::TreeEdge(0, 3, _) => Control::Prune,
+            DfsEvent::Finish(v, _) => {
+                finished.push(v);
+                Control::Continue
+            }
+            DfsEvent::Discover(2, _) => Control::Break,
+            _ => Control::Continue,
+        });
+        assert_eq!(control, Control::Break);
+        assert!(finished.is_empty());
+    }
+}
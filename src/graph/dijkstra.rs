@@ -1,5 +1,5 @@
 //! Dijkstra's algorithm
-use super::Graph;
+use super::{EdgeCount, Graph, IntoNeighbors, VertexCount};
 use std::cmp::Reverse;
 
 impl Graph {
@@ -11,25 +11,103 @@ impl Graph {
     /// Panics if `weights.len() != self.edge_count()`.
     #[must_use]
     pub fn dijkstra(&self, weights: &[u64], start: usize) -> Vec<u64> {
-        assert_eq!(self.edge_count(), weights.len());
-        let mut dist = vec![u64::max_value(); self.len()];
-        let mut heap = std::collections::BinaryHeap::new();
-
-        dist[start] = 0;
-        heap.push((Reverse(0), start));
-        while let Some((Reverse(dist_u), u)) = heap.pop() {
-            if dist[u] == dist_u {
-                for (v, e) in self.neighbors(u) {
-                    let alt_cost = dist_u.saturating_add(weights[e]);
-                    if alt_cost < dist[v] {
-                        dist[v] = alt_cost;
-                        heap.push((Reverse(alt_cost), v));
-                    }
+        dijkstra(self, weights, start)
+    }
+}
+
+/// Dijkstra's algorithm on any graph. See [`Graph::dijkstra`].
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`.
+#[must_use]
+pub fn dijkstra<G>(graph: G, weights: &[u64], start: usize) -> Vec<u64>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    search(graph, weights, start, None, |_, _| true).0
+}
+
+/// Runs Dijkstra's algorithm from `start`, stopping early once `end` is
+/// settled, and only following the edges `(vertex, edge)` that `allowed`
+/// accepts. Returns the distances and the `(vertex, edge)` each vertex was
+/// reached from.
+fn search<G>(
+    graph: G,
+    weights: &[u64],
+    start: usize,
+    end: Option<usize>,
+    allowed: impl Fn(usize, usize) -> bool,
+) -> (Vec<u64>, Vec<Option<(usize, usize)>>)
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let mut dist = vec![u64::MAX; graph.vertex_count()];
+    let mut parent = vec![None; graph.vertex_count()];
+    let mut heap = std::collections::BinaryHeap::new();
+
+    dist[start] = 0;
+    heap.push((Reverse(0), start));
+    while let Some((Reverse(dist_u), u)) = heap.pop() {
+        if Some(u) == end {
+            break;
+        }
+        if dist[u] == dist_u {
+            for (v, e) in graph.neighbors(u).filter(|&(v, e)| allowed(v, e)) {
+                let alt_cost = dist_u.saturating_add(weights[e]);
+                if alt_cost < dist[v] {
+                    dist[v] = alt_cost;
+                    parent[v] = Some((u, e));
+                    heap.push((Reverse(alt_cost), v));
                 }
             }
         }
-        dist
     }
+    (dist, parent)
+}
+
+/// Returns the weight and edges of a shortest path from `start` to `end`.
+///
+/// The path avoids the banned vertices and edges; `None` is returned if there
+/// is no such path, including when `start` or `end` is banned.
+/// Time complexity: O(|E| log |V|)
+///
+/// # Panics
+///
+/// Panics if `weights` or `banned_edges` don't have one entry per edge, or
+/// `banned_vertices` doesn't have one entry per vertex.
+pub fn shortest_path<G>(
+    graph: G,
+    weights: &[u64],
+    start: usize,
+    end: usize,
+    banned_vertices: &[bool],
+    banned_edges: &[bool],
+) -> Option<(u64, Vec<usize>)>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    assert_eq!(graph.edge_count(), banned_edges.len());
+    assert_eq!(graph.vertex_count(), banned_vertices.len());
+    if banned_vertices[start] || banned_vertices[end] {
+        return None;
+    }
+    let (dist, parent) = search(graph, weights, start, Some(end), |v, e| {
+        !banned_vertices[v] && !banned_edges[e]
+    });
+    if dist[end] == u64::MAX {
+        return None;
+    }
+    let mut path = vec![];
+    let mut node = end;
+    while let Some((u, e)) = parent[node] {
+        path.push(e);
+        node = u;
+    }
+    path.reverse();
+    Some((dist[end], path))
 }
 
 #[cfg(test)]
@@ -57,6 +135,24 @@ mod tests {
         ]);
         let weights = [3, 8, 2, 1, 7, 4, 2, 2, 6];
         let dist = graph.dijkstra(&weights, 1);
-        assert_eq!(dist, vec![u64::max_value(), 0, 3, 6, 4, 2]);
+        assert_eq!(dist, vec![u64::MAX, 0, 3, 6, 4, 2]);
+
+        let (no_vertices, no_edges) = (vec![false; 6], vec![false; 9]);
+        assert_eq!(
+            shortest_path(&graph, &weights, 1, 3, &no_vertices, &no_edges),
+            Some((6, vec![0, 3, 7]))
+        );
+        let mut banned_edges = no_edges.clone();
+        banned_edges[7] = true;
+        assert_eq!(
+            shortest_path(&graph, &weights, 1, 3, &no_vertices, &banned_edges),
+            Some((8, vec![1]))
+        );
+        let mut banned_vertices = no_vertices;
+        banned_vertices[4] = true;
+        assert_eq!(
+            shortest_path(&graph, &weights, 1, 4, &banned_vertices, &no_edges),
+            None
+        );
     }
 }
@@ -1,32 +1,25 @@
 //! Dominance algorithms
+//!
 //! <https://en.wikipedia.org/wiki/Dominator_(graph_theory)>
 //! <https://www.cs.rice.edu/~keith/EMBED/dom.pdf>
 //! <https://github.com/static-analysis-engineering/CodeHawk-Binary/blob/master/chb/app/Cfg.py>
-// use super::Graph;
-
-use super::Graph;
+use super::{traversal::post_order, IntoNeighbors, VertexCount};
 
 /// Computes the reverse-postorder of a graph.
-fn reverse_post_order(graph: &Graph, start: usize) -> Vec<usize> {
-    let mut ordering = graph.post_order(start).collect::<Vec<_>>();
+fn reverse_post_order<G: IntoNeighbors + VertexCount>(graph: G, start: usize) -> Vec<usize> {
+    let mut ordering = post_order(graph, start).collect::<Vec<_>>();
     ordering.reverse();
     ordering
 }
 
 /// Finds the nearest common dominator of two nodes using the dominators array
+/// and each node's reverse-postorder number
 fn common_dominator(
     mut node_a: usize,
     mut node_b: usize,
     doms: &[Option<usize>],
-    rpo: &[usize],
+    ordering: &[usize],
 ) -> usize {
-    let mut ordering = vec![0; rpo.len()];
-    let mut i: usize = 0;
-    for &b in rpo {
-        ordering[b] = i;
-        i += 1;
-    }
-
     while node_a != node_b {
         // The paper describes comparisons on postorder numbers; we're using
         // the reverse-postorder numbers, so we need to flip the comparison
@@ -40,12 +33,30 @@ fn common_dominator(
     node_a
 }
 
+/// Computes the immediate dominator of each vertex reachable from `start`.
+/// Unreachable vertices have no dominator.
+///
+/// # Panics
+///
+/// Panics if `start` is out of bounds.
 #[must_use]
-pub fn dominators(graph: &Graph, start: usize) -> Vec<Option<usize>> {
+pub fn dominators<G>(graph: G, start: usize) -> Vec<Option<usize>>
+where
+    G: IntoNeighbors + VertexCount,
+{
     let rpo = reverse_post_order(graph, start);
-    let transpose = graph.transpose();
+    let mut ordering = vec![0; graph.vertex_count()];
+    for (i, &b) in rpo.iter().enumerate() {
+        ordering[b] = i;
+    }
+    let mut preds = vec![vec![]; graph.vertex_count()];
+    for &u in &rpo {
+        for (v, _) in graph.neighbors(u) {
+            preds[v].push(u);
+        }
+    }
     // Initialize the dominators array
-    let mut idoms: Vec<Option<usize>> = vec![None; graph.len()];
+    let mut idoms: Vec<Option<usize>> = vec![None; graph.vertex_count()];
 
     idoms[start] = Some(start);
     let mut changed = true;
@@ -56,11 +67,11 @@ pub fn dominators(graph: &Graph, start: usize) -> Vec<Option<usize>> {
             if b == start {
                 continue;
             }
-            let allpreds = transpose.neighbors(b).collect::<Vec<_>>();
+            let allpreds = &preds[b];
             let mut new_idom = None;
 
             // Find the first (processed) predecessor of b and set it as the initial new_idom
-            for &(pred, _) in &allpreds {
+            for &pred in allpreds {
                 if idoms[pred].is_some() {
                     new_idom = Some(pred);
                     break;
@@ -69,9 +80,9 @@ pub fn dominators(graph: &Graph, start: usize) -> Vec<Option<usize>> {
             let mut new_idom = new_idom.unwrap();
 
             // Find the nearest common dominator of b's other predecessors
-            for &(p, _) in &allpreds {
+            for &p in allpreds {
                 if idoms[p].is_some() {
-                    new_idom = common_dominator(p, new_idom, &idoms, &rpo);
+                    new_idom = common_dominator(p, new_idom, &idoms, &ordering);
                 }
             }
             // Update the dominator of b if necessary
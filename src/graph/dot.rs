@@ -0,0 +1,552 @@
+//! Graphviz DOT export and import
+//! <https://graphviz.org/doc/info/lang.html>
+use super::Graph;
+use std::fmt;
+
+impl Graph {
+    /// Returns a DOT rendering of the graph, with vertices named by their
+    /// index. Labels and highlights can be added before formatting.
+    #[must_use]
+    pub fn to_dot(&self) -> Dot<'_> {
+        Dot {
+            graph: self,
+            vertex_label: None,
+            edge_label: None,
+            highlighted_vertices: vec![false; self.len()],
+            highlighted_edges: vec![false; self.edge_count()],
+            tree: vec![],
+        }
+    }
+
+    /// Parses a directed graph written in a subset of DOT. Vertices must be
+    /// named by their index, below [`MAX_DOT_VERTICES`]. Edges are numbered in
+    /// order of appearance, so this reads back what [`Graph::to_dot`] writes.
+    ///
+    /// Attributes are ignored, except that edges with `constraint=false` are
+    /// skipped: that's how [`Dot::tree`] marks its overlay, which isn't part
+    /// of the graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input isn't a `digraph` in the supported subset.
+    pub fn from_dot(input: &str) -> Result<Self, DotError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            pos: 0,
+            vmax: None,
+        };
+        let edges = parser.graph()?;
+        // `vertex` keeps indices below `MAX_DOT_VERTICES`, so this can't overflow
+        let mut graph = Self::new(parser.vmax.map_or(0, |v| v + 1), edges.len());
+        for (u, v) in edges {
+            graph.add_edge(u, v);
+        }
+        Ok(graph)
+    }
+}
+
+/// The number of vertices [`Graph::from_dot`] allows, to bound the memory an
+/// untrusted input can claim with a single large index to 16 MiB.
+pub const MAX_DOT_VERTICES: usize = 1 << 20;
+
+/// A graph being formatted as DOT. Created by [`Graph::to_dot`].
+pub struct Dot<'a> {
+    /// The graph being rendered.
+    graph: &'a Graph,
+    /// Labels each vertex.
+    vertex_label: Option<Box<dyn Fn(usize) -> String + 'a>>,
+    /// Labels each edge.
+    edge_label: Option<Box<dyn Fn(usize) -> String + 'a>>,
+    /// true if the vertex is drawn filled
+    highlighted_vertices: Vec<bool>,
+    /// true if the edge is drawn in red
+    highlighted_edges: Vec<bool>,
+    /// Extra `(parent, child)` edges drawn dashed, such as a dominator tree.
+    tree: Vec<(usize, usize)>,
+}
+
+impl<'a> Dot<'a> {
+    /// Labels each vertex with `label(vertex)`.
+    #[must_use]
+    pub fn vertex_label(mut self, label: impl Fn(usize) -> String + 'a) -> Self {
+        self.vertex_label = Some(Box::new(label));
+        self
+    }
+
+    /// Labels each edge with `label(edge)`, e.g. its weight.
+    #[must_use]
+    pub fn edge_label(mut self, label: impl Fn(usize) -> String + 'a) -> Self {
+        self.edge_label = Some(Box::new(label));
+        self
+    }
+
+    /// Highlights the given vertices.
+    #[must_use]
+    pub fn highlight_vertices(mut self, vertices: &[usize]) -> Self {
+        for &v in vertices {
+            self.highlighted_vertices[v] = true;
+        }
+        self
+    }
+
+    /// Highlights the given edges.
+    #[must_use]
+    pub fn highlight_edges(mut self, edges: &[usize]) -> Self {
+        for &e in edges {
+            self.highlighted_edges[e] = true;
+        }
+        self
+    }
+
+    /// Highlights a path given as a sequence of edges, along with its vertices.
+    #[must_use]
+    pub fn highlight_path(mut self, edges: &[usize]) -> Self {
+        let mut on_path = vec![false; self.graph.edge_count()];
+        for &e in edges {
+            on_path[e] = true;
+        }
+        for (u, v, e) in self.graph.edges() {
+            if on_path[e] {
+                self.highlighted_edges[e] = true;
+                self.highlighted_vertices[u] = true;
+                self.highlighted_vertices[v] = true;
+            }
+        }
+        self
+    }
+
+    /// Highlights one side of a cut, given as a flag per vertex, and the
+    /// edges that cross it.
+    #[must_use]
+    pub fn highlight_cut(mut self, side: &[bool]) -> Self {
+        for (u, v, e) in self.graph.edges() {
+            if side[u] != side[v] {
+                self.highlighted_edges[e] = true;
+            }
+        }
+        for (v, &s) in side.iter().enumerate() {
+            self.highlighted_vertices[v] |= s;
+        }
+        self
+    }
+
+    /// Overlays a tree given as a parent per vertex, such as the output of
+    /// [`dominators`](super::dominance::dominators), as dashed edges.
+    #[must_use]
+    pub fn tree(mut self, parents: &[Option<usize>]) -> Self {
+        for (v, &parent) in parents.iter().enumerate() {
+            if let Some(p) = parent.filter(|&p| p != v) {
+                self.tree.push((p, v));
+            }
+        }
+        self
+    }
+}
+
+/// Ends a DOT statement, writing its attribute list if there is one.
+fn write_attributes(f: &mut fmt::Formatter, attributes: &[String]) -> fmt::Result {
+    if attributes.is_empty() {
+        writeln!(f, ";")
+    } else {
+        writeln!(f, " [{}];", attributes.join(", "))
+    }
+}
+
+/// Quotes a DOT string.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl fmt::Display for Dot<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "digraph {{")?;
+        for v in 0..self.graph.len() {
+            let mut attributes = vec![];
+            if let Some(label) = &self.vertex_label {
+                attributes.push(format!("label={}", quote(&label(v))));
+            }
+            if self.highlighted_vertices[v] {
+                attributes.push("style=filled, fillcolor=lightpink".to_owned());
+            }
+            write!(f, "    {v}")?;
+            write_attributes(f, &attributes)?;
+        }
+        for (u, v, e) in self.graph.edges() {
+            let mut attributes = vec![];
+            if let Some(label) = &self.edge_label {
+                attributes.push(format!("label={}", quote(&label(e))));
+            }
+            if self.highlighted_edges[e] {
+                attributes.push("color=red, penwidth=2".to_owned());
+            }
+            write!(f, "    {u} -> {v}")?;
+            write_attributes(f, &attributes)?;
+        }
+        for &(u, v) in &self.tree {
+            writeln!(
+                f,
+                "    {u} -> {v} [style=dashed, color=blue, constraint=false];"
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// An error from parsing DOT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DotError {
+    /// A token that isn't allowed here, and the line it's on.
+    UnexpectedToken(usize, String),
+    /// The input ended before the graph was closed.
+    UnexpectedEnd,
+    /// A vertex that isn't named by an index below [`MAX_DOT_VERTICES`], and
+    /// the line it's on.
+    InvalidVertex(usize, String),
+    /// The input is an undirected `graph`.
+    Undirected,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedToken(line, token) => {
+                write!(f, "line {line}: unexpected token `{token}`")
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+            Self::InvalidVertex(line, id) => {
+                write!(f, "line {line}: vertex `{id}` isn't a valid index")
+            }
+            Self::Undirected => write!(f, "expected a digraph"),
+        }
+    }
+}
+
+impl std::error::Error for DotError {}
+
+/// A DOT token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// An identifier, number or quoted string.
+    Id(String),
+    /// Punctuation: `{`, `}`, `[`, `]`, `;`, `,`, `=`, `->` or `--`.
+    Symbol(&'static str),
+}
+
+/// Splits DOT input into tokens with their line numbers, skipping comments.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, DotError> {
+    const SYMBOLS: [&str; 9] = ["->", "--", "{", "}", "[", "]", ";", ",", "="];
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if c == '\n' {
+            line += 1;
+            rest = &rest[1..];
+        } else if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if rest.starts_with("//") || c == '#' {
+            rest = rest.find('\n').map_or("", |i| &rest[i..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment.find("*/").ok_or(DotError::UnexpectedEnd)?;
+            line += comment[..end].matches('\n').count();
+            rest = &comment[end + 2..];
+        } else if let Some(&symbol) = SYMBOLS.iter().find(|&&s| rest.starts_with(s)) {
+            tokens.push((line, Token::Symbol(symbol)));
+            rest = &rest[symbol.len()..];
+        } else if c == '"' {
+            let mut value = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            let end = loop {
+                match chars.next().ok_or(DotError::UnexpectedEnd)? {
+                    (i, '"') => break i,
+                    (_, '\\') => {
+                        // Other escapes, like `\n` in labels, are for Graphviz
+                        let (_, escaped) = chars.next().ok_or(DotError::UnexpectedEnd)?;
+                        if !matches!(escaped, '"' | '\\') {
+                            value.push('\\');
+                        }
+                        value.push(escaped);
+                    }
+                    (_, c) => {
+                        line += usize::from(c == '\n');
+                        value.push(c);
+                    }
+                }
+            };
+            tokens.push((line, Token::Id(value)));
+            rest = &rest[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err(DotError::UnexpectedToken(line, c.to_string()));
+            }
+            tokens.push((line, Token::Id(rest[..end].to_owned())));
+            rest = &rest[end..];
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive-descent parser for the supported subset of DOT.
+struct Parser {
+    /// The tokens to parse, with their line numbers.
+    tokens: Vec<(usize, Token)>,
+    /// Index of the next token.
+    pos: usize,
+    /// The largest vertex index seen so far.
+    vmax: Option<usize>,
+}
+
+impl Parser {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    /// Consumes and returns the next token and its line.
+    fn next(&mut self) -> Result<(usize, Token), DotError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token.ok_or(DotError::UnexpectedEnd)
+    }
+
+    /// Consumes the next token if it's `symbol`.
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        self.pos += usize::from(found);
+        found
+    }
+
+    /// Consumes `symbol` or fails.
+    fn expect(&mut self, symbol: &str) -> Result<(), DotError> {
+        match self.next()? {
+            (_, Token::Symbol(s)) if s == symbol => Ok(()),
+            (line, token) => Err(unexpected(line, token)),
+        }
+    }
+
+    /// Consumes an identifier or fails.
+    fn id(&mut self) -> Result<(usize, String), DotError> {
+        match self.next()? {
+            (line, Token::Id(id)) => Ok((line, id)),
+            (line, token) => Err(unexpected(line, token)),
+        }
+    }
+
+    /// Consumes a vertex index or fails.
+    fn vertex(&mut self) -> Result<usize, DotError> {
+        let (line, id) = self.id()?;
+        let v = match id.parse() {
+            Ok(v) if v < MAX_DOT_VERTICES => v,
+            _ => return Err(DotError::InvalidVertex(line, id)),
+        };
+        self.vmax = self.vmax.max(Some(v));
+        Ok(v)
+    }
+
+    /// Parses a whole graph and returns its edges.
+    fn graph(&mut self) -> Result<Vec<(usize, usize)>, DotError> {
+        let (line, mut keyword) = self.id()?;
+        if keyword.eq_ignore_ascii_case("strict") {
+            keyword = self.id()?.1;
+        }
+        if keyword.eq_ignore_ascii_case("graph") {
+            return Err(DotError::Undirected);
+        } else if !keyword.eq_ignore_ascii_case("digraph") {
+            return Err(DotError::UnexpectedToken(line, keyword));
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.id()?;
+        }
+        self.expect("{")?;
+
+        let mut edges = vec![];
+        while !self.eat("}") {
+            if self.eat(";") {
+                continue;
+            }
+            let (line, id) = self.id()?;
+            if ["graph", "node", "edge"].contains(&id.to_ascii_lowercase().as_str()) {
+                self.attributes()?;
+            } else if self.eat("=") {
+                self.id()?;
+            } else {
+                self.pos -= 1;
+                let mut u = self.vertex()?;
+                let mut statement = vec![];
+                while self.eat("->") {
+                    let v = self.vertex()?;
+                    statement.push((u, v));
+                    u = v;
+                }
+                if self.eat("--") {
+                    return Err(DotError::UnexpectedToken(line, "--".to_owned()));
+                }
+                let overlay = self
+                    .attributes()?
+                    .iter()
+                    .any(|(key, value)| key == "constraint" && value == "false");
+                if !overlay {
+                    edges.extend(statement);
+                }
+            }
+        }
+        if let Some((line, token)) = self.tokens.get(self.pos).cloned() {
+            return Err(unexpected(line, token));
+        }
+        Ok(edges)
+    }
+
+    /// Parses any number of attribute lists into `(key, value)` pairs. A key
+    /// without a value gets an empty one.
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, DotError> {
+        let mut attributes = vec![];
+        while self.eat("[") {
+            while !self.eat("]") {
+                let key = self.id()?.1;
+                let value = if self.eat("=") {
+                    self.id()?.1
+                } else {
+                    String::new()
+                };
+                attributes.push((key, value));
+                let _ = self.eat(",") || self.eat(";");
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+/// Builds the error for an unexpected token.
+fn unexpected(line: usize, token: Token) -> DotError {
+    match token {
+        Token::Id(id) => DotError::UnexpectedToken(line, id),
+        Token::Symbol(s) => DotError::UnexpectedToken(line, s.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_dot() {
+        let graph = Graph::from([(0, 1), (1, 2), (0, 2)]);
+        assert_eq!(
+            graph.to_dot().to_string(),
+            "digraph {\n    0;\n    1;\n    2;\n    0 -> 1;\n    1 -> 2;\n    0 -> 2;\n}\n"
+        );
+
+        let weights = [3, 4, 5];
+        let dot = graph
+            .to_dot()
+            .vertex_label(|v| format!("v\"{v}\""))
+            .edge_label(|e| weights[e].to_string())
+            .highlight_path(&[2])
+            .tree(&[Some(0), Some(0), Some(0)])
+            .to_string();
+        assert_eq!(
+            dot,
+            r#"digraph {
+    0 [label="v\"0\"", style=filled, fillcolor=lightpink];
+    1 [label="v\"1\""];
+    2 [label="v\"2\"", style=filled, fillcolor=lightpink];
+    0 -> 1 [label="3"];
+    1 -> 2 [label="4"];
+    0 -> 2 [label="5", color=red, penwidth=2];
+    0 -> 1 [style=dashed, color=blue, constraint=false];
+    0 -> 2 [style=dashed, color=blue, constraint=false];
+}
+"#
+        );
+
+        let dot = graph
+            .to_dot()
+            .highlight_cut(&[true, false, false])
+            .to_string();
+        assert!(dot.contains("0 [style=filled, fillcolor=lightpink];"));
+        assert!(dot.contains("0 -> 1 [color=red, penwidth=2];"));
+        assert!(dot.contains("1 -> 2;"));
+    }
+
+    #[test]
+    fn test_from_dot() {
+        let graph = Graph::from([(2, 3), (2, 4), (4, 1), (1, 2)]);
+        let parsed = Graph::from_dot(&graph.to_dot().vertex_label(|v| v.to_string()).to_string());
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.len(), 5);
+        assert_eq!(parsed.edge_count(), 4);
+        for v in 0..5 {
+            assert_eq!(
+                parsed.neighbors(v).collect::<Vec<_>>(),
+                graph.neighbors(v).collect::<Vec<_>>()
+            );
+        }
+
+        let parsed = Graph::from_dot(
+            "strict digraph G {\n  // comment\n  rankdir=LR; node [shape=box]\n  0 -> 1 -> 2 [label=\"a\"]\n  /* 3 */ 3\n}",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed.neighbors(1).collect::<Vec<_>>(), [(2, 1)]);
+
+        assert_eq!(
+            Graph::from_dot("graph { 0 -- 1 }").err(),
+            Some(DotError::Undirected)
+        );
+        assert_eq!(
+            Graph::from_dot("digraph {\n 0 -> a\n}").err(),
+            Some(DotError::InvalidVertex(2, "a".to_owned()))
+        );
+        assert_eq!(
+            Graph::from_dot("digraph {\n 0 -> ;\n}").err(),
+            Some(DotError::UnexpectedToken(2, ";".to_owned()))
+        );
+        assert_eq!(
+            Graph::from_dot("digraph { 0").err(),
+            Some(DotError::UnexpectedEnd)
+        );
+        for id in ["18446744073709551615", "18446744073709551616", "1048576"] {
+            assert_eq!(
+                Graph::from_dot(&format!("digraph {{ {id} }}")).err(),
+                Some(DotError::InvalidVertex(1, id.to_owned()))
+            );
+        }
+
+        // The tree overlay isn't read back as edges
+        let dot = graph
+            .to_dot()
+            .tree(&[None, Some(2), None, Some(2), Some(2)]);
+        let parsed = Graph::from_dot(&dot.to_string()).unwrap();
+        assert_eq!(parsed.edges(), graph.edges());
+    }
+
+    #[test]
+    fn test_escapes() {
+        // Labels read back from the output are written out the same way
+        let graph = Graph::from([(0, 1), (1, 2)]);
+        let labels = [r"a\b", r#"say "hi""#, r"\\n"];
+        let dot = graph.to_dot().vertex_label(|v| labels[v].to_owned());
+        let written = dot.to_string();
+        assert!(written.contains(r#"0 [label="a\\b"];"#));
+
+        let tokens = tokenize(&written).unwrap();
+        let read = tokens
+            .windows(3)
+            .filter_map(|window| match window {
+                [(_, Token::Id(key)), (_, Token::Symbol("=")), (_, Token::Id(value))]
+                    if key == "label" =>
+                {
+                    Some(value.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(read, labels);
+        let rewritten = graph.to_dot().vertex_label(|v| read[v].clone()).to_string();
+        assert_eq!(rewritten, written);
+        assert_eq!(Graph::from_dot(&written).unwrap().edges(), graph.edges());
+    }
+}
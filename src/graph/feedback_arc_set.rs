@@ -0,0 +1,257 @@
+//! Feedback arc set heuristics
+//! <https://en.wikipedia.org/wiki/Feedback_arc_set>
+//!
+//! Finding a minimum feedback arc set is NP-hard. These heuristics order the
+//! vertices so that few edges point backwards, and return those edges. Every
+//! self-loop is a feedback edge.
+use super::{scc::strongly_connected_components, Graph, IntoNeighbors, VertexCount};
+use std::{cmp::Reverse, collections::BTreeSet};
+
+impl Graph {
+    /// Returns edges whose removal makes the graph acyclic. See
+    /// [`scc_feedback_arc_set`].
+    #[must_use]
+    pub fn feedback_arc_set(&self) -> FeedbackArcSet {
+        scc_feedback_arc_set(self)
+    }
+}
+
+/// A set of edges whose removal makes a graph acyclic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedbackArcSet {
+    /// The ids of the edges to remove, in increasing order.
+    pub edges: Vec<usize>,
+    /// A topological order of the graph without those edges. The removed
+    /// edges are exactly the ones that don't point forwards in it.
+    pub order: Vec<usize>,
+}
+
+/// The Eades–Lin–Smyth greedy heuristic.
+/// <https://doi.org/10.1016/0020-0190(93)90079-O>
+///
+/// Repeatedly moves sinks to the back of the order and sources to the front.
+/// When there are neither, the vertex with the most outgoing edges beyond its
+/// incoming ones goes to the front. At most half of the edges that aren't
+/// self-loops are removed.
+/// Time complexity: O((|V| + |E|) log |V|)
+pub fn feedback_arc_set<G>(graph: G) -> FeedbackArcSet
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let adjacency = Adjacency::new(graph);
+    let vertices = (0..graph.vertex_count()).collect::<Vec<_>>();
+    let order = adjacency.order(&vertices);
+    adjacency.feedback(order)
+}
+
+/// Like [`feedback_arc_set`], but orders each strongly connected component
+/// separately.
+///
+/// Components are placed in topological order, so no edge between components
+/// is removed.
+/// Time complexity: O((|V| + |E|) log |V|)
+pub fn scc_feedback_arc_set<G>(graph: G) -> FeedbackArcSet
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let adjacency = Adjacency::new(graph);
+    let order = strongly_connected_components(graph)
+        .iter()
+        .flat_map(|component| adjacency.order(component))
+        .collect();
+    adjacency.feedback(order)
+}
+
+/// Outgoing and incoming `(vertex, edge)` pairs, without self-loops.
+struct Adjacency {
+    /// The `(head, edge)` pairs leaving each vertex.
+    outgoing: Vec<Vec<(usize, usize)>>,
+    /// The `(tail, edge)` pairs entering each vertex.
+    incoming: Vec<Vec<(usize, usize)>>,
+    /// The self-loops, which are always removed.
+    loops: Vec<usize>,
+}
+
+impl Adjacency {
+    fn new<G>(graph: G) -> Self
+    where
+        G: IntoNeighbors + VertexCount,
+    {
+        let n = graph.vertex_count();
+        let mut loops = vec![];
+        let outgoing = (0..n)
+            .map(|u| {
+                loops.extend(graph.neighbors(u).filter(|&(v, _)| v == u).map(|(_, e)| e));
+                graph
+                    .neighbors(u)
+                    .filter(|&(v, _)| v != u)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut incoming = vec![vec![]; n];
+        for (u, list) in outgoing.iter().enumerate() {
+            for &(v, e) in list {
+                incoming[v].push((u, e));
+            }
+        }
+        Self {
+            outgoing,
+            incoming,
+            loops,
+        }
+    }
+
+    /// Orders `vertices` by the Eades–Lin–Smyth heuristic, ignoring edges to
+    /// other vertices.
+    fn order(&self, vertices: &[usize]) -> Vec<usize> {
+        let n = self.outgoing.len();
+        let mut active = vec![false; n];
+        for &v in vertices {
+            active[v] = true;
+        }
+        let mut out_degree = vec![0; n];
+        let mut in_degree = vec![0; n];
+        for &u in vertices {
+            for &(v, _) in self.outgoing[u].iter().filter(|&&(v, _)| active[v]) {
+                out_degree[u] += 1;
+                in_degree[v] += 1;
+            }
+        }
+        // Orders by out-degree minus in-degree, shifted to stay unsigned
+        let shift = in_degree.iter().max().copied().unwrap_or_default();
+        let key = |v: usize, out_degree: &[usize], in_degree: &[usize]| {
+            (Reverse(out_degree[v] + shift - in_degree[v]), v)
+        };
+        let mut queue = vertices
+            .iter()
+            .map(|&v| key(v, &out_degree, &in_degree))
+            .collect::<BTreeSet<_>>();
+        let mut sinks = vertices
+            .iter()
+            .copied()
+            .filter(|&v| out_degree[v] == 0)
+            .collect::<Vec<_>>();
+        let mut sources = vertices
+            .iter()
+            .copied()
+            .filter(|&v| in_degree[v] == 0)
+            .collect::<Vec<_>>();
+
+        let (mut front, mut back) = (vec![], vec![]);
+        while !queue.is_empty() {
+            let u = if let Some(v) = sinks.pop() {
+                if !active[v] {
+                    continue;
+                }
+                back.push(v);
+                v
+            } else if let Some(v) = sources.pop() {
+                if !active[v] {
+                    continue;
+                }
+                front.push(v);
+                v
+            } else if let Some((_, v)) = queue.first().copied() {
+                front.push(v);
+                v
+            } else {
+                break;
+            };
+            active[u] = false;
+            queue.remove(&key(u, &out_degree, &in_degree));
+            for &(v, _) in self.outgoing[u].iter().filter(|&&(v, _)| active[v]) {
+                queue.remove(&key(v, &out_degree, &in_degree));
+                in_degree[v] -= 1;
+                queue.insert(key(v, &out_degree, &in_degree));
+                if in_degree[v] == 0 {
+                    sources.push(v);
+                }
+            }
+            for &(v, _) in self.incoming[u].iter().filter(|&&(v, _)| active[v]) {
+                queue.remove(&key(v, &out_degree, &in_degree));
+                out_degree[v] -= 1;
+                queue.insert(key(v, &out_degree, &in_degree));
+                if out_degree[v] == 0 {
+                    sinks.push(v);
+                }
+            }
+        }
+        front.extend(back.into_iter().rev());
+        front
+    }
+
+    /// Returns the edges that don't point forwards in `order`.
+    fn feedback(&self, order: Vec<usize>) -> FeedbackArcSet {
+        let mut position = vec![0; order.len()];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+        let mut edges = self.loops.clone();
+        for (u, list) in self.outgoing.iter().enumerate() {
+            edges.extend(
+                list.iter()
+                    .filter(|&&(v, _)| position[v] < position[u])
+                    .map(|&(_, e)| e),
+            );
+        }
+        edges.sort_unstable();
+        FeedbackArcSet { edges, order }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::generators::gnp;
+
+    /// Checks that removing the feedback edges leaves `order` topological.
+    fn assert_acyclic(graph: &Graph, fas: &FeedbackArcSet) {
+        let mut position = vec![0; graph.len()];
+        for (i, &v) in fas.order.iter().enumerate() {
+            position[v] = i;
+        }
+        for (u, v, e) in graph.edges() {
+            let removed = fas.edges.binary_search(&e).is_ok();
+            assert_eq!(removed, position[u] >= position[v], "edge {e}");
+        }
+    }
+
+    #[test]
+    fn test_feedback_arc_set() {
+        /*
+            0 -> 1 -> 2 -> 0, 2 -> 3 -> 4 -> 3, and 4 -> 4
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 4)]);
+        let fas = graph.feedback_arc_set();
+        assert_eq!(fas.edges.len(), 3);
+        assert!(fas.edges.contains(&6));
+        assert_acyclic(&graph, &fas);
+        assert_eq!(&fas.order[3..], [3, 4]);
+
+        let fas = feedback_arc_set(&graph);
+        assert_eq!(fas.edges.len(), 3);
+        assert_acyclic(&graph, &fas);
+
+        let dag = Graph::from([(0, 2), (2, 1), (1, 3)]);
+        assert_eq!(
+            dag.feedback_arc_set(),
+            FeedbackArcSet {
+                edges: vec![],
+                order: vec![0, 2, 1, 3]
+            }
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        for seed in 0..10 {
+            let graph = gnp(30, 0.2, seed);
+            let m = graph.edge_count();
+            for fas in [feedback_arc_set(&graph), scc_feedback_arc_set(&graph)] {
+                assert_acyclic(&graph, &fas);
+                assert!(fas.edges.len() <= m / 2);
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! Seeded random and structured graph generators.
+//!
+//! Random generators take a `seed` and always produce the same graph for the
+//! same arguments. Generators of naturally undirected graphs (grids, tori,
+//! complete graphs and stars) add both directions of each edge.
+//! No generator adds self-loops.
+use super::Graph;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+/// `SplitMix64` pseudo-random number generator.
+/// <https://prng.di.unimi.it/splitmix64.c>
+struct Rng(u64);
+
+impl Rng {
+    /// Returns the next 64 random bits.
+    const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniform random number in `0..n`.
+    const fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+        // Reject the top partial range so that every residue is equally likely
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                #[allow(clippy::cast_possible_truncation)]
+                return (x % n) as usize;
+            }
+        }
+    }
+
+    /// Returns a uniform random number in `[0, 1)`.
+    #[allow(clippy::cast_precision_loss)]
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Shuffles a slice in place. <https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle>
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Maps an index in `0..n * (n - 1)` to the ordered pair of distinct vertices
+/// it stands for.
+const fn arc(n: usize, k: usize) -> (usize, usize) {
+    let (u, w) = (k / (n - 1), k % (n - 1));
+    (u, if w >= u { w + 1 } else { w })
+}
+
+/// Erdős–Rényi G(n, p).
+///
+/// Each of the `n * (n - 1)` possible edges is present independently with
+/// probability `p`.
+/// <https://en.wikipedia.org/wiki/Erd%C5%91s%E2%80%93R%C3%A9nyi_model>
+/// Time complexity: O(|V| + |E|), by skipping absent edges geometrically.
+#[must_use]
+pub fn gnp(n: usize, p: f64, seed: u64) -> Graph {
+    let arcs = n * n.saturating_sub(1);
+    let mut graph = Graph::new(n, 0);
+    if p <= 0.0 || arcs == 0 {
+        return graph;
+    }
+    if p >= 1.0 {
+        return complete(n);
+    }
+    let mut rng = Rng(seed);
+    let log_q = (1.0 - p).ln();
+    let mut index: usize = 0;
+    loop {
+        // Number of absent edges before the next present one
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let skip = ((1.0 - rng.unit()).ln() / log_q).floor() as usize;
+        index = index.saturating_add(skip);
+        if index >= arcs {
+            return graph;
+        }
+        let (u, v) = arc(n, index);
+        graph.add_edge(u, v);
+        index += 1;
+    }
+}
+
+/// Erdős–Rényi G(n, m): `m` distinct edges chosen uniformly at random.
+/// Edges are added in lexicographic order.
+///
+/// # Panics
+///
+/// Panics if `m > n * (n - 1)`.
+#[must_use]
+pub fn gnm(n: usize, m: usize, seed: u64) -> Graph {
+    let arcs = n * n.saturating_sub(1);
+    assert!(m <= arcs, "G(n, m) can have at most n * (n - 1) edges");
+    // Floyd's algorithm for sampling without replacement
+    let mut rng = Rng(seed);
+    let mut chosen = HashSet::with_capacity(m);
+    for j in arcs - m..arcs {
+        let t = rng.below(j + 1);
+        if !chosen.insert(t) {
+            chosen.insert(j);
+        }
+    }
+    let mut chosen = chosen.into_iter().collect::<Vec<_>>();
+    chosen.sort_unstable();
+
+    let mut graph = Graph::new(n, m);
+    for k in chosen {
+        let (u, v) = arc(n, k);
+        graph.add_edge(u, v);
+    }
+    graph
+}
+
+/// A random DAG: vertices are shuffled into a random topological order, then
+/// each edge from an earlier to a later vertex is present with probability `p`.
+#[must_use]
+pub fn random_dag(n: usize, p: f64, seed: u64) -> Graph {
+    let mut rng = Rng(seed);
+    let mut order = (0..n).collect::<Vec<_>>();
+    rng.shuffle(&mut order);
+    let mut graph = Graph::new(n, 0);
+    for i in 0..n {
+        for j in i + 1..n {
+            if rng.unit() < p {
+                graph.add_edge(order[i], order[j]);
+            }
+        }
+    }
+    graph
+}
+
+/// A uniformly random labeled tree on `n` vertices, with edges directed away
+/// from vertex 0. Built by decoding a random Prüfer sequence.
+/// <https://en.wikipedia.org/wiki/Pr%C3%BCfer_sequence>
+#[must_use]
+pub fn random_tree(n: usize, seed: u64) -> Graph {
+    let mut rng = Rng(seed);
+    let sequence = (0..n.saturating_sub(2))
+        .map(|_| rng.below(n))
+        .collect::<Vec<_>>();
+    let mut degree = vec![1; n];
+    for &v in &sequence {
+        degree[v] += 1;
+    }
+    let mut leaves = (0..n)
+        .filter(|&v| degree[v] == 1)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut adjacency = vec![vec![]; n];
+    let mut link = |u: usize, v: usize| {
+        adjacency[u].push(v);
+        adjacency[v].push(u);
+    };
+    for &v in &sequence {
+        // There are always at least two leaves left
+        let Some(Reverse(leaf)) = leaves.pop() else {
+            break;
+        };
+        link(leaf, v);
+        degree[v] -= 1;
+        if degree[v] == 1 {
+            leaves.push(Reverse(v));
+        }
+    }
+    if let (Some(Reverse(u)), Some(Reverse(v))) = (leaves.pop(), leaves.pop()) {
+        link(u, v);
+    }
+
+    // Orient the edges away from the root
+    let mut graph = Graph::new(n, n.saturating_sub(1));
+    let mut visited = vec![false; n];
+    let mut stack = (n > 0).then_some(0).into_iter().collect::<Vec<_>>();
+    while let Some(u) = stack.pop() {
+        visited[u] = true;
+        for &v in &adjacency[u] {
+            if !visited[v] {
+                graph.add_edge(u, v);
+                stack.push(v);
+            }
+        }
+    }
+    graph
+}
+
+/// A `rows` x `cols` grid. Vertex `r * cols + c` is linked to its
+/// horizontal and vertical neighbors.
+#[must_use]
+pub fn grid(rows: usize, cols: usize) -> Graph {
+    lattice(rows, cols, false)
+}
+
+/// A `rows` x `cols` grid whose rows and columns wrap around.
+#[must_use]
+pub fn torus(rows: usize, cols: usize) -> Graph {
+    lattice(rows, cols, true)
+}
+
+/// Builds a grid, optionally wrapping around its edges.
+fn lattice(rows: usize, cols: usize, wrap: bool) -> Graph {
+    let mut graph = Graph::new(rows * cols, 4 * rows * cols);
+    let mut link = |u: usize, v: usize| {
+        graph.add_edge(u, v);
+        graph.add_edge(v, u);
+    };
+    for r in 0..rows {
+        for c in 0..cols {
+            let u = r * cols + c;
+            if c + 1 < cols || (wrap && cols > 2) {
+                link(u, r * cols + (c + 1) % cols);
+            }
+            if r + 1 < rows || (wrap && rows > 2) {
+                link(u, (r + 1) % rows * cols + c);
+            }
+        }
+    }
+    graph
+}
+
+/// The complete graph on `n` vertices.
+#[must_use]
+pub fn complete(n: usize) -> Graph {
+    let mut graph = Graph::new(n, n * n.saturating_sub(1));
+    for u in 0..n {
+        for v in (0..n).filter(|&v| v != u) {
+            graph.add_edge(u, v);
+        }
+    }
+    graph
+}
+
+/// A star with center 0 and leaves `1..n`.
+#[must_use]
+pub fn star(n: usize) -> Graph {
+    let mut graph = Graph::new(n, 2 * n.saturating_sub(1));
+    for v in 1..n {
+        graph.add_edge(0, v);
+        graph.add_edge(v, 0);
+    }
+    graph
+}
+
+/// Barabási–Albert preferential attachment.
+///
+/// Starting from `m` isolated vertices, each new vertex adds edges to `m`
+/// distinct existing vertices, chosen with probability proportional to their
+/// degree.
+/// Edges point from the new vertex to the existing ones.
+/// <https://en.wikipedia.org/wiki/Barab%C3%A1si%E2%80%93Albert_model>
+///
+/// # Panics
+///
+/// Panics if `m == 0` or `m >= n`.
+#[must_use]
+pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Graph {
+    assert!(0 < m && m < n, "need 0 < m < n");
+    let mut rng = Rng(seed);
+    let mut graph = Graph::new(n, (n - m) * m);
+    // Each vertex appears here once per edge it's on
+    let mut endpoints = Vec::with_capacity(2 * (n - m) * m);
+    let mut targets = (0..m).collect::<Vec<_>>();
+    let mut chosen = vec![false; n];
+    for v in m..n {
+        for &t in &targets {
+            graph.add_edge(v, t);
+            endpoints.push(t);
+            endpoints.push(v);
+        }
+        targets.clear();
+        while targets.len() < m {
+            let t = endpoints[rng.below(endpoints.len())];
+            if !chosen[t] {
+                chosen[t] = true;
+                targets.push(t);
+            }
+        }
+        for &t in &targets {
+            chosen[t] = false;
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::dominance::dominators;
+
+    /// Checks that a graph has no self-loops or parallel edges.
+    fn assert_simple(graph: &Graph) {
+        let edges = graph.edges();
+        assert!(edges.iter().all(|&(u, v, _)| u != v));
+        let pairs = edges
+            .iter()
+            .map(|&(u, v, _)| (u, v))
+            .collect::<HashSet<_>>();
+        assert_eq!(pairs.len(), edges.len());
+    }
+
+    #[test]
+    fn test_random() {
+        assert_eq!(gnp(30, 0.2, 7).edges(), gnp(30, 0.2, 7).edges());
+        assert_ne!(gnp(30, 0.2, 7).edges(), gnp(30, 0.2, 8).edges());
+        assert_simple(&gnp(30, 0.2, 7));
+        assert_eq!(gnp(5, 1.0, 0).edge_count(), 20);
+        assert_eq!(gnp(5, 0.0, 0).edge_count(), 0);
+
+        let graph = gnm(10, 40, 3);
+        assert_eq!(graph.edge_count(), 40);
+        assert_simple(&graph);
+        assert_eq!(gnm(4, 12, 3).edge_count(), 12);
+
+        for seed in 0..20 {
+            let dag = random_dag(12, 0.3, seed);
+            assert!(dag.is_dag());
+            assert_simple(&dag);
+        }
+    }
+
+    #[test]
+    fn test_random_tree() {
+        for seed in 0..20 {
+            let tree = random_tree(15, seed);
+            assert_eq!(tree.edge_count(), 14);
+            // every vertex is reachable from 0, and its dominator is its parent
+            let idoms = dominators(&tree, 0);
+            for (u, v, _) in tree.edges() {
+                assert_eq!(idoms[v], Some(u));
+            }
+        }
+        assert_eq!(random_tree(1, 0).edge_count(), 0);
+        assert_eq!(random_tree(2, 0).edges(), [(0, 1, 0)]);
+    }
+
+    #[test]
+    fn test_structured() {
+        assert_eq!(grid(3, 4).edge_count(), 2 * (3 * 3 + 2 * 4));
+        assert_eq!(torus(3, 4).edge_count(), 2 * 2 * 12);
+        assert_simple(&torus(3, 4));
+        assert_eq!(complete(4).edge_count(), 12);
+        assert_eq!(star(4).neighbors(0).count(), 3);
+
+        let graph = barabasi_albert(50, 3, 1);
+        assert_eq!(graph.edge_count(), 47 * 3);
+        assert_simple(&graph);
+        assert_eq!(graph.edges(), barabasi_albert(50, 3, 1).edges());
+    }
+}
@@ -0,0 +1,306 @@
+//! Heavy-light decomposition
+//! <https://en.wikipedia.org/wiki/Heavy-light_decomposition>
+//!
+//! Splits a rooted tree into chains so that any path crosses O(log |V|) of
+//! them. Vertices are numbered so that every chain, and every subtree, is a
+//! contiguous range of positions, which can index a segment tree or any other
+//! range structure.
+use super::{IntoNeighbors, VertexCount};
+use std::{cmp::Reverse, ops::Range};
+
+/// A contiguous run of positions on a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The positions covered.
+    pub range: Range<usize>,
+    /// True if the path walks the range from its end to its start, that is,
+    /// towards the root.
+    pub reversed: bool,
+}
+
+/// A heavy-light decomposition of a tree.
+#[derive(Debug, Clone)]
+pub struct HeavyLight {
+    /// Maps a vertex to its parent; the root has none.
+    parent: Vec<Option<usize>>,
+    /// Maps a vertex to its distance from the root.
+    depth: Vec<usize>,
+    /// Maps a vertex to the number of vertices in its subtree.
+    size: Vec<usize>,
+    /// Maps a vertex to the topmost vertex of its chain.
+    head: Vec<usize>,
+    /// Maps a vertex to its position.
+    position: Vec<usize>,
+    /// Maps a position to its vertex.
+    order: Vec<usize>,
+}
+
+impl HeavyLight {
+    /// Decomposes the tree formed by the vertices reachable from `root`. Edges
+    /// may point away from the root or both ways; an edge back to a vertex's
+    /// parent is ignored.
+    /// Time complexity: O(|V| + |E|)
+    ///
+    /// # Panics
+    ///
+    /// Panics if some vertex isn't reachable from `root`, or is reachable
+    /// along more than one path.
+    #[must_use]
+    pub fn new<G>(graph: G, root: usize) -> Self
+    where
+        G: IntoNeighbors + VertexCount,
+    {
+        let n = graph.vertex_count();
+        let mut parent = vec![None; n];
+        let mut depth = vec![0; n];
+        let mut children = vec![vec![]; n];
+        let mut seen = vec![false; n];
+        let mut preorder = Vec::with_capacity(n);
+        let mut stack = vec![root];
+        seen[root] = true;
+        while let Some(u) = stack.pop() {
+            preorder.push(u);
+            for (v, _) in graph.neighbors(u) {
+                if Some(v) == parent[u] {
+                    continue;
+                }
+                assert!(!seen[v], "graph is not a tree");
+                seen[v] = true;
+                (parent[v], depth[v]) = (Some(u), depth[u] + 1);
+                children[u].push(v);
+                stack.push(v);
+            }
+        }
+        assert_eq!(preorder.len(), n, "graph is not connected");
+
+        let mut size = vec![1; n];
+        for &u in preorder.iter().rev() {
+            if let Some(p) = parent[u] {
+                size[p] += size[u];
+            }
+        }
+        // Put each vertex's heavy child first
+        for list in &mut children {
+            if let Some(i) = (0..list.len()).max_by_key(|&i| (size[list[i]], Reverse(i))) {
+                list.swap(0, i);
+            }
+        }
+
+        let mut head = vec![root; n];
+        let mut position = vec![0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut heads = vec![root];
+        while let Some(top) = heads.pop() {
+            let mut u = top;
+            loop {
+                head[u] = top;
+                position[u] = order.len();
+                order.push(u);
+                heads.extend(children[u].iter().skip(1).rev());
+                match children[u].first() {
+                    Some(&heavy) => u = heavy,
+                    None => break,
+                }
+            }
+        }
+        Self {
+            parent,
+            depth,
+            size,
+            head,
+            position,
+            order,
+        }
+    }
+
+    /// Returns the position of `node`.
+    #[must_use]
+    pub fn position(&self, node: usize) -> usize {
+        self.position[node]
+    }
+
+    /// Returns the vertices in order of position.
+    #[must_use]
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns the topmost vertex of the chain containing `node`.
+    #[must_use]
+    pub fn head(&self, node: usize) -> usize {
+        self.head[node]
+    }
+
+    /// Returns every chain, from its head downwards, in order of position.
+    #[must_use]
+    pub fn chains(&self) -> Vec<Vec<usize>> {
+        let mut chains: Vec<Vec<usize>> = vec![];
+        for &v in &self.order {
+            if self.head[v] == v {
+                chains.push(vec![]);
+            }
+            if let Some(chain) = chains.last_mut() {
+                chain.push(v);
+            }
+        }
+        chains
+    }
+
+    /// Returns the positions of the subtree rooted at `node`.
+    #[must_use]
+    pub fn subtree(&self, node: usize) -> Range<usize> {
+        self.position[node]..self.position[node] + self.size[node]
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v`.
+    /// Time complexity: O(log |V|)
+    #[must_use]
+    pub fn lca(&self, mut u: usize, mut v: usize) -> usize {
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            u = self.parent[self.head[u]].unwrap_or_default();
+        }
+        if self.depth[u] < self.depth[v] {
+            u
+        } else {
+            v
+        }
+    }
+
+    /// Splits the path from `u` to `v` into segments of positions, listed in
+    /// order from `u` to `v`. Segments on the way up from `u` are reversed.
+    /// Time complexity: O(log |V|)
+    #[must_use]
+    pub fn path_segments(&self, u: usize, v: usize) -> Vec<Segment> {
+        self.segments(u, v, true)
+    }
+
+    /// Like [`HeavyLight::path_segments`], but leaves out the lowest common
+    /// ancestor. If each edge's value is stored at its child, these segments
+    /// cover exactly the edges of the path.
+    #[must_use]
+    pub fn edge_segments(&self, u: usize, v: usize) -> Vec<Segment> {
+        self.segments(u, v, false)
+    }
+
+    /// Splits the path from `u` to `v` into segments, climbing whichever
+    /// chain head is deeper. The lowest common ancestor is kept if `with_lca`.
+    fn segments(&self, mut u: usize, mut v: usize, with_lca: bool) -> Vec<Segment> {
+        let (mut up, mut down) = (vec![], vec![]);
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] >= self.depth[self.head[v]] {
+                let top = self.head[u];
+                up.push(self.position[top]..self.position[u] + 1);
+                u = self.parent[top].unwrap_or_default();
+            } else {
+                let top = self.head[v];
+                down.push(self.position[top]..self.position[v] + 1);
+                v = self.parent[top].unwrap_or_default();
+            }
+        }
+        // `u` and `v` are now on the same chain, and the shallower is the LCA
+        let skip = usize::from(!with_lca);
+        if self.depth[u] >= self.depth[v] {
+            up.push(self.position[v] + skip..self.position[u] + 1);
+        } else {
+            down.push(self.position[u] + skip..self.position[v] + 1);
+        }
+
+        let up = up.into_iter().map(|range| Segment {
+            range,
+            reversed: true,
+        });
+        let down = down.into_iter().rev().map(|range| Segment {
+            range,
+            reversed: false,
+        });
+        up.chain(down).filter(|s| !s.range.is_empty()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{generators::random_tree, lca::BinaryLifting, Graph};
+
+    #[test]
+    fn test_decomposition() {
+        /*
+                  0
+                / | \
+               1  2  3
+              /|     |
+             4 5     6
+             |
+             7
+        */
+        let tree = Graph::from([(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (4, 7)]);
+        let hld = HeavyLight::new(&tree, 0);
+        assert_eq!(hld.order(), [0, 1, 4, 7, 5, 2, 3, 6]);
+        assert_eq!(
+            hld.chains(),
+            [vec![0, 1, 4, 7], vec![5], vec![2], vec![3, 6]]
+        );
+        assert_eq!(hld.subtree(1), 1..5);
+        assert_eq!(hld.lca(7, 5), 1);
+        assert_eq!(hld.lca(6, 2), 0);
+
+        // 7 -> 4 -> 1 -> 0 -> 3 -> 6
+        assert_eq!(
+            hld.path_segments(7, 6),
+            [
+                Segment {
+                    range: 0..4,
+                    reversed: true
+                },
+                Segment {
+                    range: 6..8,
+                    reversed: false
+                },
+            ]
+        );
+        assert_eq!(
+            hld.edge_segments(7, 6),
+            [
+                Segment {
+                    range: 1..4,
+                    reversed: true
+                },
+                Segment {
+                    range: 6..8,
+                    reversed: false
+                },
+            ]
+        );
+        assert_eq!(hld.edge_segments(4, 4), []);
+    }
+
+    #[test]
+    fn test_random_paths() {
+        for seed in 0..5 {
+            let tree = random_tree(60, seed);
+            let hld = HeavyLight::new(&tree, 0);
+            let mut parent = vec![None; tree.len()];
+            for (u, v, _) in tree.edges() {
+                parent[v] = Some(u);
+            }
+            let lifting = BinaryLifting::new(&parent);
+            for u in 0..tree.len() {
+                for v in 0..tree.len() {
+                    let lca = hld.lca(u, v);
+                    assert_eq!(Some(lca), lifting.lca(u, v));
+                    // the segments cover exactly the path's vertices
+                    let covered = hld
+                        .path_segments(u, v)
+                        .into_iter()
+                        .map(|s| s.range.len())
+                        .sum::<usize>();
+                    let length = lifting.depth(u) + lifting.depth(v) - 2 * lifting.depth(lca);
+                    assert_eq!(covered, length + 1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Reading and writing graphs in standard file formats.
+//!
+//! - Plain edge lists: one `from to [weight]` line per edge.
+//! - DIMACS shortest-path graphs (`.gr`):
+//!   <http://www.diag.uniroma1.it/challenge9/format.shtml#graph>
+//! - SNAP edge lists, with `#` comment lines: <https://snap.stanford.edu/data/>
+//!
+//! Readers return the graph with one weight per edge; edges without a weight
+//! get weight 1. Edges are numbered in file order.
+use super::Graph;
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+/// An error from reading a graph file. Line numbers start at 1.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The reader failed.
+    Io(io::Error),
+    /// A line has the wrong number of fields, or an unknown line type.
+    InvalidLine(usize, String),
+    /// A field that should be a number isn't one.
+    InvalidNumber(usize, String),
+    /// A vertex is out of the declared range.
+    VertexOutOfRange(usize, u64),
+    /// A vertex count, or a vertex index, is at least [`MAX_READ_VERTICES`].
+    TooManyVertices(usize, u64),
+    /// A DIMACS arc came before the problem line, or there was no problem line.
+    MissingProblemLine(usize),
+    /// The number of edges doesn't match the DIMACS problem line.
+    EdgeCountMismatch {
+        /// Edges declared on the problem line.
+        expected: usize,
+        /// Edges found.
+        found: usize,
+    },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::InvalidLine(line, text) => write!(f, "line {line}: invalid line `{text}`"),
+            Self::InvalidNumber(line, field) => {
+                write!(f, "line {line}: `{field}` isn't a valid number")
+            }
+            Self::VertexOutOfRange(line, v) => write!(f, "line {line}: vertex {v} out of range"),
+            Self::TooManyVertices(line, v) => write!(
+                f,
+                "line {line}: {v} is beyond the limit of {MAX_READ_VERTICES} vertices"
+            ),
+            Self::MissingProblemLine(line) => {
+                write!(f, "line {line}: expected a `p sp` problem line first")
+            }
+            Self::EdgeCountMismatch { expected, found } => {
+                write!(f, "expected {expected} edges, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Parses a numeric field.
+fn number(line: usize, field: &str) -> Result<u64, ReadError> {
+    field
+        .parse()
+        .map_err(|_| ReadError::InvalidNumber(line, field.to_owned()))
+}
+
+/// Converts a vertex number, or a vertex count, to an index below
+/// [`MAX_READ_VERTICES`].
+fn vertex(line: usize, v: u64) -> Result<usize, ReadError> {
+    match usize::try_from(v) {
+        Ok(v) if v < MAX_READ_VERTICES => Ok(v),
+        _ => Err(ReadError::TooManyVertices(line, v)),
+    }
+}
+
+/// The number of vertices the readers allow, to bound the memory an untrusted
+/// input can claim with a single large index or vertex count.
+pub const MAX_READ_VERTICES: usize = 1 << 20;
+
+/// The most edges to allocate room for up front, whatever a header claims.
+const MAX_PREALLOCATED_EDGES: usize = 1 << 20;
+
+/// Builds a graph with `n` vertices from `(from, to)` pairs.
+fn build(n: usize, edges: &[(usize, usize)]) -> Graph {
+    let mut graph = Graph::new(n, edges.len());
+    for &(u, v) in edges {
+        graph.add_edge(u, v);
+    }
+    graph
+}
+
+/// Reads edge-list lines of the form `from to [weight]`, skipping blank lines
+/// and lines that start with `comment`.
+fn read_pairs<R: BufRead>(
+    reader: R,
+    comment: Option<char>,
+    weighted: bool,
+) -> Result<(Graph, Vec<u64>), ReadError> {
+    let mut pairs = vec![];
+    let mut weights = vec![];
+    let mut n = 0;
+    for (i, text) in reader.lines().enumerate() {
+        let (line, text) = (i + 1, text?);
+        let text = text.trim();
+        if text.is_empty() || comment.is_some_and(|c| text.starts_with(c)) {
+            continue;
+        }
+        let fields = text.split_whitespace().collect::<Vec<_>>();
+        let weight = match (fields.len(), weighted) {
+            (2, _) => 1,
+            (3, true) => number(line, fields[2])?,
+            _ => return Err(ReadError::InvalidLine(line, text.to_owned())),
+        };
+        let mut ends = [0; 2];
+        for (end, field) in ends.iter_mut().zip(&fields) {
+            *end = vertex(line, number(line, field)?)?;
+            // `vertex` keeps indices below `MAX_READ_VERTICES`, so this can't
+            // overflow
+            n = n.max(*end + 1);
+        }
+        pairs.push(ends.into());
+        weights.push(weight);
+    }
+    Ok((build(n, &pairs), weights))
+}
+
+/// Reads a plain edge list, with one `from to [weight]` line per edge and
+/// 0-based vertices. The graph has as many vertices as the largest one listed.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, a line is malformed, or a vertex is at
+/// least [`MAX_READ_VERTICES`].
+pub fn read_edge_list<R: BufRead>(reader: R) -> Result<(Graph, Vec<u64>), ReadError> {
+    read_pairs(reader, None, true)
+}
+
+/// Writes a plain edge list, with weights if given.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Panics
+///
+/// Panics if `weights` is given and `weights.len() != graph.edge_count()`.
+pub fn write_edge_list<W: Write>(
+    graph: &Graph,
+    weights: Option<&[u64]>,
+    mut writer: W,
+) -> io::Result<()> {
+    if let Some(weights) = weights {
+        assert_eq!(graph.edge_count(), weights.len());
+    }
+    for (u, v, e) in graph.edges() {
+        match weights {
+            Some(weights) => writeln!(writer, "{u} {v} {}", weights[e])?,
+            None => writeln!(writer, "{u} {v}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Reads a SNAP edge list: tab- or space-separated `from to` lines, with
+/// `#` comment lines. Every edge gets weight 1.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, a line is malformed, or a vertex is at
+/// least [`MAX_READ_VERTICES`].
+pub fn read_snap<R: BufRead>(reader: R) -> Result<(Graph, Vec<u64>), ReadError> {
+    read_pairs(reader, Some('#'), false)
+}
+
+/// Writes a SNAP edge list with the usual header comments.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn write_snap<W: Write>(graph: &Graph, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "# Directed graph")?;
+    writeln!(
+        writer,
+        "# Nodes: {} Edges: {}",
+        graph.len(),
+        graph.edge_count()
+    )?;
+    writeln!(writer, "# FromNodeId\tToNodeId")?;
+    for (u, v, _) in graph.edges() {
+        writeln!(writer, "{u}\t{v}")?;
+    }
+    Ok(())
+}
+
+/// Reads a DIMACS shortest-path graph: `c` comment lines, one
+/// `p sp <vertices> <arcs>` problem line, then `a <from> <to> <weight>` arc
+/// lines with 1-based vertices, which are shifted to 0-based.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, a line is malformed, the problem line
+/// declares [`MAX_READ_VERTICES`] vertices or more, or the arcs don't match
+/// it.
+pub fn read_dimacs<R: BufRead>(reader: R) -> Result<(Graph, Vec<u64>), ReadError> {
+    let mut graph: Option<Graph> = None;
+    let mut expected = 0;
+    let mut weights = vec![];
+    let mut last_line = 0;
+    for (i, text) in reader.lines().enumerate() {
+        let (line, text) = (i + 1, text?);
+        last_line = line;
+        let fields = text.split_whitespace().collect::<Vec<_>>();
+        let invalid = || ReadError::InvalidLine(line, text.trim().to_owned());
+        match fields.first() {
+            None | Some(&"c") => {}
+            Some(&"p") => {
+                if graph.is_some() || fields.len() != 4 || fields[1] != "sp" {
+                    return Err(invalid());
+                }
+                let n = vertex(line, number(line, fields[2])?)?;
+                let arcs = number(line, fields[3])?;
+                expected = usize::try_from(arcs)
+                    .map_err(|_| ReadError::InvalidNumber(line, fields[3].to_owned()))?;
+                // The arcs are still to come, so don't trust the count
+                let capacity = expected.min(MAX_PREALLOCATED_EDGES);
+                graph = Some(Graph::new(n, capacity));
+                weights.reserve(capacity);
+            }
+            Some(&"a") => {
+                let graph = graph.as_mut().ok_or(ReadError::MissingProblemLine(line))?;
+                if fields.len() != 4 {
+                    return Err(invalid());
+                }
+                let endpoint = |field| {
+                    let v = number(line, field)?;
+                    match usize::try_from(v) {
+                        Ok(u @ 1..) if u <= graph.len() => Ok(u - 1),
+                        _ => Err(ReadError::VertexOutOfRange(line, v)),
+                    }
+                };
+                let u = endpoint(fields[1])?;
+                let v = endpoint(fields[2])?;
+                graph.add_edge(u, v);
+                weights.push(number(line, fields[3])?);
+            }
+            Some(_) => return Err(invalid()),
+        }
+    }
+    let graph = graph.ok_or(ReadError::MissingProblemLine(last_line + 1))?;
+    if graph.edge_count() != expected {
+        return Err(ReadError::EdgeCountMismatch {
+            expected,
+            found: graph.edge_count(),
+        });
+    }
+    Ok((graph, weights))
+}
+
+/// Writes a DIMACS shortest-path graph with 1-based vertices.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`.
+pub fn write_dimacs<W: Write>(graph: &Graph, weights: &[u64], mut writer: W) -> io::Result<()> {
+    assert_eq!(graph.edge_count(), weights.len());
+    writeln!(writer, "p sp {} {}", graph.len(), graph.edge_count())?;
+    for (u, v, e) in graph.edges() {
+        writeln!(writer, "a {} {} {}", u + 1, v + 1, weights[e])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that two graphs have the same edges in the same order.
+    fn assert_same(a: &Graph, b: &Graph) {
+        assert_eq!(a.len(), b.len());
+        assert_eq!(a.edges(), b.edges());
+    }
+
+    #[test]
+    fn test_edge_list() {
+        let (graph, weights) = read_edge_list(b"0 1 5\n\n1 2\n 2 0 7 \n".as_slice()).unwrap();
+        assert_same(&graph, &Graph::from([(0, 1), (1, 2), (2, 0)]));
+        assert_eq!(weights, [5, 1, 7]);
+
+        let mut out = vec![];
+        write_edge_list(&graph, Some(&weights), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0 1 5\n1 2 1\n2 0 7\n");
+
+        assert!(matches!(
+            read_edge_list(b"0 1\n1 x\n".as_slice()),
+            Err(ReadError::InvalidNumber(2, field)) if field == "x"
+        ));
+        assert!(matches!(
+            read_edge_list(b"0 1 2 3\n".as_slice()),
+            Err(ReadError::InvalidLine(1, _))
+        ));
+        for (input, at, v) in [
+            ("0 18446744073709551615\n", 1, u64::MAX),
+            ("0 18446744073709551614\n", 1, u64::MAX - 1),
+            ("0 1\n1048576 0\n", 2, 1 << 20),
+        ] {
+            assert!(matches!(
+                read_edge_list(input.as_bytes()),
+                Err(ReadError::TooManyVertices(line, found)) if line == at && found == v
+            ));
+        }
+        let (graph, _) = read_edge_list(b"1048575 0\n".as_slice()).unwrap();
+        assert_eq!(graph.len(), MAX_READ_VERTICES);
+    }
+
+    #[test]
+    fn test_snap() {
+        let input = "# Directed graph\n# FromNodeId\tToNodeId\n0\t4\n4\t1\n";
+        let (graph, weights) = read_snap(input.as_bytes()).unwrap();
+        assert_same(&graph, &Graph::from([(0, 4), (4, 1)]));
+        assert_eq!(weights, [1, 1]);
+
+        let mut out = vec![];
+        write_snap(&graph, &mut out).unwrap();
+        let (reread, _) = read_snap(out.as_slice()).unwrap();
+        assert_same(&reread, &graph);
+
+        assert!(matches!(
+            read_snap(b"0\t1\t2\n".as_slice()),
+            Err(ReadError::InvalidLine(1, _))
+        ));
+    }
+
+    #[test]
+    fn test_dimacs() {
+        let input = "c sample\np sp 3 3\nc arcs\na 1 2 7\na 2 3 3\na 3 1 5\n";
+        let (graph, weights) = read_dimacs(input.as_bytes()).unwrap();
+        assert_same(&graph, &Graph::from([(0, 1), (1, 2), (2, 0)]));
+        assert_eq!(graph.dijkstra(&weights, 0), [0, 7, 10]);
+
+        let mut out = vec![];
+        write_dimacs(&graph, &weights, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "p sp 3 3\na 1 2 7\na 2 3 3\na 3 1 5\n"
+        );
+
+        assert!(matches!(
+            read_dimacs(b"a 1 2 3\n".as_slice()),
+            Err(ReadError::MissingProblemLine(1))
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 2 1\na 1 3 4\n".as_slice()),
+            Err(ReadError::VertexOutOfRange(2, 3))
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 2 2\na 1 2 4\n".as_slice()),
+            Err(ReadError::EdgeCountMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 2 18446744073709551615\na 1 2 4\n".as_slice()),
+            Err(ReadError::EdgeCountMismatch {
+                expected: usize::MAX,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 18446744073709551615 0\n".as_slice()),
+            Err(ReadError::TooManyVertices(1, u64::MAX))
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 100000000000 0\n".as_slice()),
+            Err(ReadError::TooManyVertices(1, 100_000_000_000))
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 2 1\na 0 1 4\n".as_slice()),
+            Err(ReadError::VertexOutOfRange(2, 0))
+        ));
+        assert!(matches!(
+            read_dimacs(b"p sp 2 1\nx\n".as_slice()),
+            Err(ReadError::InvalidLine(2, _))
+        ));
+    }
+}
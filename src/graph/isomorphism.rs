@@ -0,0 +1,493 @@
+//! VF2 graph and subgraph isomorphism
+//! <https://en.wikipedia.org/wiki/Graph_isomorphism_problem>
+//!
+//! A mapping is a `Vec` indexed by pattern vertex, giving the target vertex it
+//! is matched with. Parallel edges are compared by count: every pattern edge
+//! between two matched vertices must be accepted by the edge callback for at
+//! least one target edge between their images.
+use super::{Graph, IntoNeighbors, VertexCount};
+
+impl Graph {
+    /// Returns true if the graph is isomorphic to `other`.
+    #[must_use]
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        isomorphisms(self, other).next().is_some()
+    }
+}
+
+/// Enumerates the isomorphisms between `pattern` and `target`.
+pub fn isomorphisms<G, H>(pattern: G, target: H) -> Vf2
+where
+    G: IntoNeighbors + VertexCount,
+    H: IntoNeighbors + VertexCount,
+{
+    Vf2::new(pattern, target, Mode::Isomorphism)
+}
+
+/// Enumerates the embeddings of `pattern` as an induced subgraph of `target`:
+/// two pattern vertices are adjacent exactly when their images are.
+pub fn induced_subgraph_isomorphisms<G, H>(pattern: G, target: H) -> Vf2
+where
+    G: IntoNeighbors + VertexCount,
+    H: IntoNeighbors + VertexCount,
+{
+    Vf2::new(pattern, target, Mode::Induced)
+}
+
+/// Enumerates the embeddings of `pattern` as a subgraph of `target`: every
+/// pattern edge has a counterpart, but the target may have extra edges
+/// between matched vertices.
+pub fn subgraph_isomorphisms<G, H>(pattern: G, target: H) -> Vf2
+where
+    G: IntoNeighbors + VertexCount,
+    H: IntoNeighbors + VertexCount,
+{
+    Vf2::new(pattern, target, Mode::Subgraph)
+}
+
+/// What kind of mapping is searched for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Isomorphism,
+    Induced,
+    Subgraph,
+}
+
+/// Which terminal set a pattern vertex was chosen from.
+#[derive(Clone, Copy)]
+enum Class {
+    Out,
+    In,
+    Other,
+}
+
+/// One level of the search: a pattern vertex and the target vertices tried
+/// for it so far.
+#[derive(Clone, Copy)]
+struct Frame {
+    /// The pattern vertex being matched.
+    node: usize,
+    /// The terminal set `node` was taken from.
+    class: Class,
+    /// The next target vertex to try.
+    next: usize,
+    /// The target vertex `node` is currently matched with.
+    matched: Option<usize>,
+}
+
+/// One of the two graphs together with its half of the search state.
+struct Side {
+    /// Outgoing `(vertex, edge)` pairs of each vertex.
+    out: Vec<Vec<(usize, usize)>>,
+    /// Incoming `(vertex, edge)` pairs of each vertex.
+    inc: Vec<Vec<(usize, usize)>>,
+    /// Maps a vertex to the vertex of the other graph it is matched with.
+    core: Vec<Option<usize>>,
+    /// Depth at which a vertex became a successor of a matched vertex, 0 if not.
+    out_depth: Vec<usize>,
+    /// Depth at which a vertex became a predecessor of a matched vertex, 0 if not.
+    in_depth: Vec<usize>,
+}
+
+impl Side {
+    fn new<G: IntoNeighbors + VertexCount>(graph: G) -> Self {
+        let n = graph.vertex_count();
+        let out = (0..n)
+            .map(|u| graph.neighbors(u).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut inc = vec![vec![]; n];
+        for (u, list) in out.iter().enumerate() {
+            for &(v, e) in list {
+                inc[v].push((u, e));
+            }
+        }
+        Self {
+            out,
+            inc,
+            core: vec![None; n],
+            out_depth: vec![0; n],
+            in_depth: vec![0; n],
+        }
+    }
+
+    const fn len(&self) -> usize {
+        self.core.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.out.iter().map(Vec::len).sum()
+    }
+
+    /// Matches `node` with `other` and grows the terminal sets.
+    fn push(&mut self, node: usize, other: usize, depth: usize) {
+        self.core[node] = Some(other);
+        for (list, depth_of) in [
+            (&self.out[node], &mut self.out_depth),
+            (&self.inc[node], &mut self.in_depth),
+        ] {
+            for &v in list.iter().map(|(v, _)| v).chain([&node]) {
+                if depth_of[v] == 0 {
+                    depth_of[v] = depth;
+                }
+            }
+        }
+    }
+
+    /// Undoes the [`Side::push`] of `node` at `depth`.
+    fn pop(&mut self, node: usize, depth: usize) {
+        self.core[node] = None;
+        for (list, depth_of) in [
+            (&self.out[node], &mut self.out_depth),
+            (&self.inc[node], &mut self.in_depth),
+        ] {
+            for &v in list.iter().map(|(v, _)| v).chain([&node]) {
+                if depth_of[v] == depth {
+                    depth_of[v] = 0;
+                }
+            }
+        }
+    }
+
+    /// Returns true if unmatched `v` belongs to the terminal set `class`.
+    fn is_in(&self, class: Class, v: usize) -> bool {
+        self.core[v].is_none()
+            && match class {
+                Class::Out => self.out_depth[v] != 0,
+                Class::In => self.in_depth[v] != 0,
+                Class::Other => true,
+            }
+    }
+
+    /// Returns the edges from `u` to `v`.
+    fn edges(&self, u: usize, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.out[u]
+            .iter()
+            .filter(move |&&(w, _)| w == v)
+            .map(|&(_, e)| e)
+    }
+
+    /// Counts the unmatched vertices among `neighbors` other than `node`:
+    /// those in the in-terminal set, those in the out-terminal set, those in
+    /// neither, and all of them.
+    fn classify(&self, neighbors: &[(usize, usize)], node: usize) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for &(v, _) in neighbors {
+            if v == node || self.core[v].is_some() {
+                continue;
+            }
+            let (is_in, is_out) = (self.in_depth[v] != 0, self.out_depth[v] != 0);
+            counts[0] += usize::from(is_in);
+            counts[1] += usize::from(is_out);
+            counts[2] += usize::from(!is_in && !is_out);
+            counts[3] += 1;
+        }
+        counts
+    }
+}
+
+/// Accepts any pair of vertices or edges.
+const fn any(_: usize, _: usize) -> bool {
+    true
+}
+
+/// Iterator over the mappings found by the VF2 algorithm.
+/// <https://doi.org/10.1109/TPAMI.2004.75>
+///
+/// Mappings are produced in lexicographic order of the target vertices
+/// assigned to the pattern vertices, in the order the search visits them.
+/// Time complexity: O(|V|! |V|) in the worst case, far less in practice.
+pub struct Vf2<VM = fn(usize, usize) -> bool, EM = fn(usize, usize) -> bool> {
+    /// The graph being searched for.
+    pattern: Side,
+    /// The graph being searched in.
+    target: Side,
+    /// The kind of mapping searched for.
+    mode: Mode,
+    /// Decides whether a pattern vertex may be matched with a target vertex.
+    vertex_match: VM,
+    /// Decides whether a pattern edge may be matched with a target edge.
+    edge_match: EM,
+    /// The search path; empty once the search is over.
+    stack: Vec<Frame>,
+    /// True if the empty mapping of an empty pattern is still to be yielded.
+    empty: bool,
+}
+
+impl Vf2 {
+    fn new<G, H>(pattern: G, target: H, mode: Mode) -> Self
+    where
+        G: IntoNeighbors + VertexCount,
+        H: IntoNeighbors + VertexCount,
+    {
+        let (pattern, target) = (Side::new(pattern), Side::new(target));
+        let possible = match mode {
+            Mode::Isomorphism => {
+                pattern.len() == target.len() && pattern.edge_count() == target.edge_count()
+            }
+            Mode::Induced => pattern.len() <= target.len(),
+            Mode::Subgraph => {
+                pattern.len() <= target.len() && pattern.edge_count() <= target.edge_count()
+            }
+        };
+        let mut vf2 = Self {
+            pattern,
+            target,
+            mode,
+            vertex_match: any,
+            edge_match: any,
+            stack: vec![],
+            empty: false,
+        };
+        if possible {
+            match vf2.next_frame() {
+                Some(frame) => vf2.stack.push(frame),
+                None => vf2.empty = true,
+            }
+        }
+        vf2
+    }
+}
+
+impl<VM, EM> Vf2<VM, EM> {
+    /// Only matches pattern vertex `u` with target vertex `v` if
+    /// `vertex_match(u, v)` returns true.
+    pub fn vertex_match<F>(self, vertex_match: F) -> Vf2<F, EM>
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        Vf2 {
+            pattern: self.pattern,
+            target: self.target,
+            mode: self.mode,
+            vertex_match,
+            edge_match: self.edge_match,
+            stack: self.stack,
+            empty: self.empty,
+        }
+    }
+
+    /// Only matches pattern edge `e` with target edge `f` if
+    /// `edge_match(e, f)` returns true.
+    pub fn edge_match<F>(self, edge_match: F) -> Vf2<VM, F>
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        Vf2 {
+            pattern: self.pattern,
+            target: self.target,
+            mode: self.mode,
+            vertex_match: self.vertex_match,
+            edge_match,
+            stack: self.stack,
+            empty: self.empty,
+        }
+    }
+
+    /// Picks the next pattern vertex to match, preferring successors and then
+    /// predecessors of the vertices matched so far.
+    fn next_frame(&self) -> Option<Frame> {
+        let p = &self.pattern;
+        [Class::Out, Class::In, Class::Other]
+            .into_iter()
+            .find_map(|class| {
+                (0..p.len())
+                    .find(|&v| p.is_in(class, v))
+                    .map(|node| (node, class))
+            })
+            .map(|(node, class)| Frame {
+                node,
+                class,
+                next: 0,
+                matched: None,
+            })
+    }
+}
+
+impl<VM, EM> Vf2<VM, EM>
+where
+    VM: FnMut(usize, usize) -> bool,
+    EM: FnMut(usize, usize) -> bool,
+{
+    /// Returns true if the pattern edges `a -> b` fit the target edges `c -> d`.
+    fn edges_match(&mut self, (a, b): (usize, usize), (c, d): (usize, usize)) -> bool {
+        let found = self.target.edges(c, d).collect::<Vec<_>>();
+        let mut wanted = 0;
+        for e in self.pattern.edges(a, b) {
+            wanted += 1;
+            if !found.iter().any(|&f| (self.edge_match)(e, f)) {
+                return false;
+            }
+        }
+        match self.mode {
+            Mode::Subgraph => wanted <= found.len(),
+            _ => wanted == found.len(),
+        }
+    }
+
+    /// Returns true if matching pattern vertex `n` with target vertex `m` is
+    /// consistent with the vertices already matched, and leaves enough
+    /// unmatched neighbors to go on.
+    fn is_feasible(&mut self, n: usize, m: usize) -> bool {
+        if !(self.vertex_match)(n, m) {
+            return false;
+        }
+        let (p, t) = (&self.pattern, &self.target);
+        let image = |s: usize| if s == n { Some(m) } else { p.core[s] };
+        let preimage = |u: usize| if u == m { Some(n) } else { t.core[u] };
+
+        // Edges between the new pair and the matched vertices, seen from
+        // both graphs, with self-loops counted once
+        let mut pairs = vec![];
+        for &(s, _) in &p.out[n] {
+            pairs.extend(image(s).map(|u| ((n, s), (m, u))));
+        }
+        for &(s, _) in p.inc[n].iter().filter(|&&(s, _)| s != n) {
+            pairs.extend(image(s).map(|u| ((s, n), (u, m))));
+        }
+        if self.mode != Mode::Subgraph {
+            for &(u, _) in &t.out[m] {
+                pairs.extend(preimage(u).map(|s| ((n, s), (m, u))));
+            }
+            for &(u, _) in t.inc[m].iter().filter(|&&(u, _)| u != m) {
+                pairs.extend(preimage(u).map(|s| ((s, n), (u, m))));
+            }
+        }
+
+        // Look-ahead on the unmatched neighbors
+        for (pn, tm) in [(&p.out[n], &t.out[m]), (&p.inc[n], &t.inc[m])] {
+            let (ours, theirs) = (p.classify(pn, n), t.classify(tm, m));
+            let fits = match self.mode {
+                Mode::Isomorphism => ours == theirs,
+                Mode::Induced => (0..3).all(|i| ours[i] <= theirs[i]),
+                Mode::Subgraph => [0, 1, 3].into_iter().all(|i| ours[i] <= theirs[i]),
+            };
+            if !fits {
+                return false;
+            }
+        }
+        pairs.into_iter().all(|(x, y)| self.edges_match(x, y))
+    }
+}
+
+impl<VM, EM> Iterator for Vf2<VM, EM>
+where
+    VM: FnMut(usize, usize) -> bool,
+    EM: FnMut(usize, usize) -> bool,
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if std::mem::take(&mut self.empty) {
+            return Some(vec![]);
+        }
+        loop {
+            let depth = self.stack.len();
+            let frame = *self.stack.last()?;
+            if let Some(m) = frame.matched {
+                self.pattern.pop(frame.node, depth);
+                self.target.pop(m, depth);
+            }
+            // A pattern vertex outside the terminal sets must be mapped
+            // outside them too when non-edges are preserved
+            let strict = matches!(frame.class, Class::Other) && self.mode != Mode::Subgraph;
+            let mut found = None;
+            for m in frame.next..self.target.len() {
+                let t = &self.target;
+                let fits = t.is_in(frame.class, m)
+                    && !(strict && (t.in_depth[m] != 0 || t.out_depth[m] != 0));
+                if fits && self.is_feasible(frame.node, m) {
+                    found = Some(m);
+                    break;
+                }
+            }
+            let Some(m) = found else {
+                self.stack.pop();
+                continue;
+            };
+            self.stack[depth - 1] = Frame {
+                next: m + 1,
+                matched: Some(m),
+                ..frame
+            };
+            self.pattern.push(frame.node, m, depth);
+            self.target.push(m, frame.node, depth);
+            match self.next_frame() {
+                Some(next) => self.stack.push(next),
+                None => return Some(self.pattern.core.iter().flatten().copied().collect()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isomorphism() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (2, 3)]);
+        let other = Graph::from([(3, 2), (2, 1), (1, 3), (1, 0)]);
+        assert!(graph.is_isomorphic(&other));
+        assert_eq!(
+            isomorphisms(&graph, &other).collect::<Vec<_>>(),
+            [vec![3, 2, 1, 0]]
+        );
+        assert!(!graph.is_isomorphic(&other.transpose()));
+        assert!(!graph.is_isomorphic(&Graph::from([(0, 1), (1, 2), (2, 0), (3, 0)])));
+
+        // a directed cycle maps onto itself in every rotation
+        let cycle = Graph::from([(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(isomorphisms(&cycle, &cycle).count(), 3);
+        let labels = ['a', 'b', 'a'];
+        assert_eq!(
+            isomorphisms(&cycle, &cycle)
+                .vertex_match(|u, v| labels[u] == labels[v])
+                .collect::<Vec<_>>(),
+            [vec![0, 1, 2]]
+        );
+        assert_eq!(
+            isomorphisms(&Graph::new(0, 0), &Graph::new(0, 0)).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_subgraph() {
+        /*
+            0 -> 1 -> 2 -> 3
+                 ^---------'
+            plus a shortcut 1 -> 3
+        */
+        let target = Graph::from([(0, 1), (1, 2), (2, 3), (3, 1), (1, 3)]);
+        let path = Graph::from([(0, 1), (1, 2)]);
+        let mut found = subgraph_isomorphisms(&path, &target).collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found,
+            [[0, 1, 2], [0, 1, 3], [1, 2, 3], [2, 3, 1], [3, 1, 2]]
+        );
+        assert_eq!(
+            induced_subgraph_isomorphisms(&path, &target).collect::<Vec<_>>(),
+            [[0, 1, 2]]
+        );
+
+        // match a self-loop only against edges with the same weight
+        let pattern = Graph::from([(0, 1), (1, 1)]);
+        let target = Graph::from([(0, 0), (1, 2), (2, 2), (3, 0), (0, 2)]);
+        let weights = [1, 1, 2, 1, 1];
+        assert_eq!(
+            subgraph_isomorphisms(&pattern, &target).collect::<Vec<_>>(),
+            [[0, 2], [1, 2], [3, 0]]
+        );
+        assert_eq!(
+            subgraph_isomorphisms(&pattern, &target)
+                .edge_match(|_, f| weights[f] == 1)
+                .collect::<Vec<_>>(),
+            [[3, 0]]
+        );
+        assert_eq!(
+            subgraph_isomorphisms(&Graph::from([(0, 1), (0, 1)]), &target).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Lowest common ancestors in rooted forests
+//! <https://en.wikipedia.org/wiki/Lowest_common_ancestor>
+//!
+//! Both structures are built from a parent array, which maps each vertex to
+//! its parent. A vertex with no parent, or that is its own parent, is a root,
+//! so the output of [`dominators`](super::dominance::dominators) can be used
+//! directly. Vertices in different trees have no common ancestor.
+//!
+//! # Panics
+//!
+//! Construction panics if the parent array has a cycle other than a root's
+//! self-loop.
+
+/// The shape of the forest described by a parent array.
+struct Forest {
+    /// The children of each vertex.
+    children: Vec<Vec<usize>>,
+    /// The roots, in increasing order.
+    roots: Vec<usize>,
+    /// Maps a vertex to its distance from its root.
+    depth: Vec<usize>,
+    /// Maps a vertex to the root of its tree.
+    root: Vec<usize>,
+}
+
+impl Forest {
+    fn new(parent: &[Option<usize>]) -> Self {
+        let n = parent.len();
+        let mut children = vec![vec![]; n];
+        let mut roots = vec![];
+        for (v, &p) in parent.iter().enumerate() {
+            match p {
+                Some(p) if p != v => children[p].push(v),
+                _ => roots.push(v),
+            }
+        }
+        let mut depth = vec![0; n];
+        let mut root = vec![usize::MAX; n];
+        let mut stack = roots.iter().map(|&r| (r, r, 0)).collect::<Vec<_>>();
+        while let Some((v, r, d)) = stack.pop() {
+            (root[v], depth[v]) = (r, d);
+            stack.extend(children[v].iter().map(|&c| (c, r, d + 1)));
+        }
+        assert!(
+            root.iter().all(|&r| r != usize::MAX),
+            "parent array has a cycle"
+        );
+        Self {
+            children,
+            roots,
+            depth,
+            root,
+        }
+    }
+}
+
+/// Binary lifting: stores the `2^j`-th ancestor of every vertex.
+/// Space: O(|V| log |V|)
+#[derive(Debug, Clone)]
+pub struct BinaryLifting {
+    /// `up[j][v]` is the `2^j`-th ancestor of `v`, or its root if that's
+    /// further than the root.
+    up: Vec<Vec<usize>>,
+    /// Maps a vertex to its distance from its root.
+    depth: Vec<usize>,
+    /// Maps a vertex to the root of its tree.
+    root: Vec<usize>,
+}
+
+impl BinaryLifting {
+    /// Builds the ancestor tables from a parent array.
+    /// Time complexity: O(|V| log |V|)
+    #[must_use]
+    pub fn new(parent: &[Option<usize>]) -> Self {
+        let Forest { depth, root, .. } = Forest::new(parent);
+        let levels = depth.iter().max().map_or(1, |&d| {
+            usize::BITS as usize - d.leading_zeros() as usize + 1
+        });
+        let mut up = Vec::with_capacity(levels);
+        up.push(
+            parent
+                .iter()
+                .enumerate()
+                .map(|(v, &p)| p.unwrap_or(v))
+                .collect::<Vec<_>>(),
+        );
+        for j in 1..levels {
+            let half = &up[j - 1];
+            let next = half.iter().map(|&a| half[a]).collect();
+            up.push(next);
+        }
+        Self { up, depth, root }
+    }
+
+    /// Returns the distance from `node` to its root.
+    #[must_use]
+    pub fn depth(&self, node: usize) -> usize {
+        self.depth[node]
+    }
+
+    /// Returns the ancestor `k` levels above `node`, where `node` is its own
+    /// 0th ancestor, or `None` if `node` isn't that deep.
+    /// Time complexity: O(log |V|)
+    #[must_use]
+    pub fn kth_ancestor(&self, mut node: usize, k: usize) -> Option<usize> {
+        if k > self.depth[node] {
+            return None;
+        }
+        for (j, up) in self.up.iter().enumerate() {
+            if k >> j & 1 == 1 {
+                node = up[node];
+            }
+        }
+        Some(node)
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v`, or `None` if they're
+    /// in different trees.
+    /// Time complexity: O(log |V|)
+    #[must_use]
+    pub fn lca(&self, u: usize, v: usize) -> Option<usize> {
+        if self.root[u] != self.root[v] {
+            return None;
+        }
+        let (u, v) = if self.depth[u] < self.depth[v] {
+            (v, u)
+        } else {
+            (u, v)
+        };
+        let (mut u, mut v) = (self.kth_ancestor(u, self.depth[u] - self.depth[v])?, v);
+        if u == v {
+            return Some(u);
+        }
+        for up in self.up.iter().rev() {
+            if up[u] != up[v] {
+                (u, v) = (up[u], up[v]);
+            }
+        }
+        Some(self.up[0][u])
+    }
+}
+
+/// Euler tour with a sparse table of range minima, for constant-time queries.
+/// <https://cp-algorithms.com/graph/lca.html>
+/// Space: O(|V| log |V|)
+#[derive(Debug, Clone)]
+pub struct EulerTour {
+    /// Maps a vertex to its first position in `tour`.
+    first: Vec<usize>,
+    /// `table[j][i]` is the shallowest vertex in `tour[i..i + 2^j]`.
+    table: Vec<Vec<usize>>,
+    /// Maps a vertex to its distance from its root.
+    depth: Vec<usize>,
+    /// Maps a vertex to the root of its tree.
+    root: Vec<usize>,
+}
+
+impl EulerTour {
+    /// Builds the tour and its sparse table from a parent array.
+    /// Time complexity: O(|V| log |V|)
+    #[must_use]
+    pub fn new(parent: &[Option<usize>]) -> Self {
+        let Forest {
+            children,
+            roots,
+            depth,
+            root,
+        } = Forest::new(parent);
+        let n = parent.len();
+        let mut tour = Vec::with_capacity(2 * n);
+        let mut first = vec![0; n];
+        for r in roots {
+            first[r] = tour.len();
+            tour.push(r);
+            let mut stack = vec![(r, children[r].iter())];
+            while let Some((_, next)) = stack.last_mut() {
+                if let Some(&v) = next.next() {
+                    first[v] = tour.len();
+                    tour.push(v);
+                    stack.push((v, children[v].iter()));
+                } else {
+                    stack.pop();
+                    if let Some(&(p, _)) = stack.last() {
+                        tour.push(p);
+                    }
+                }
+            }
+        }
+
+        let mut table = vec![tour.clone()];
+        let mut width = 1;
+        while 2 * width <= tour.len() {
+            let prev = &table[table.len() - 1];
+            let next = (0..=tour.len() - 2 * width)
+                .map(|i| {
+                    let (a, b) = (prev[i], prev[i + width]);
+                    if depth[a] <= depth[b] {
+                        a
+                    } else {
+                        b
+                    }
+                })
+                .collect();
+            table.push(next);
+            width *= 2;
+        }
+        Self {
+            first,
+            table,
+            depth,
+            root,
+        }
+    }
+
+    /// Returns the distance from `node` to its root.
+    #[must_use]
+    pub fn depth(&self, node: usize) -> usize {
+        self.depth[node]
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v`, or `None` if they're
+    /// in different trees.
+    /// Time complexity: O(1)
+    #[must_use]
+    pub fn lca(&self, u: usize, v: usize) -> Option<usize> {
+        if self.root[u] != self.root[v] {
+            return None;
+        }
+        let (start, end) = (
+            self.first[u].min(self.first[v]),
+            self.first[u].max(self.first[v]),
+        );
+        let level = (end - start + 1).ilog2() as usize;
+        let row = &self.table[level];
+        let (left, right) = (row[start], row[end + 1 - (1 << level)]);
+        Some(if self.depth[left] <= self.depth[right] {
+            left
+        } else {
+            right
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{bfs::bfs, dominance::dominators, Graph};
+
+    #[test]
+    fn test_lca() {
+        /*
+                0         5
+               / \        |
+              1   2       6
+             / \   \
+            3   4   7
+        */
+        let parent = [
+            None,
+            Some(0),
+            Some(0),
+            Some(1),
+            Some(1),
+            None,
+            Some(5),
+            Some(2),
+        ];
+        let lifting = BinaryLifting::new(&parent);
+        let tour = EulerTour::new(&parent);
+        for (u, v, lca) in [
+            (3, 4, Some(1)),
+            (3, 7, Some(0)),
+            (1, 4, Some(1)),
+            (7, 7, Some(7)),
+            (6, 5, Some(5)),
+            (3, 6, None),
+        ] {
+            assert_eq!(lifting.lca(u, v), lca);
+            assert_eq!(tour.lca(u, v), lca);
+        }
+        assert_eq!(lifting.depth(7), 2);
+        assert_eq!(tour.depth(6), 1);
+        assert_eq!(lifting.kth_ancestor(4, 0), Some(4));
+        assert_eq!(lifting.kth_ancestor(4, 2), Some(0));
+        assert_eq!(lifting.kth_ancestor(4, 3), None);
+    }
+
+    #[test]
+    fn test_dominator_tree() {
+        /*
+            0 -> 1 -> 2 -> 4
+                 |         ^
+                 '--> 3 ---'
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (1, 3), (2, 4), (3, 4)]);
+        let idoms = dominators(&graph, 0);
+        let tour = EulerTour::new(&idoms);
+        assert_eq!(tour.lca(2, 3), Some(1));
+        assert_eq!(tour.lca(4, 2), Some(1));
+
+        let tree = bfs(&graph, [0]).into_tree();
+        let parent = tree
+            .parent
+            .iter()
+            .map(|p| p.map(|(u, _)| u))
+            .collect::<Vec<_>>();
+        let lifting = BinaryLifting::new(&parent);
+        assert_eq!(lifting.lca(4, 3), Some(3));
+    }
+
+    #[test]
+    fn test_long_path() {
+        let parent = (0..1000_usize)
+            .map(|v| v.checked_sub(1))
+            .collect::<Vec<_>>();
+        let lifting = BinaryLifting::new(&parent);
+        let tour = EulerTour::new(&parent);
+        assert_eq!(lifting.kth_ancestor(999, 999), Some(0));
+        assert_eq!(lifting.lca(500, 999), Some(500));
+        assert_eq!(tour.lca(999, 500), Some(500));
+    }
+}
@@ -0,0 +1,441 @@
+//! Loop detection and loop nesting forests
+//!
+//! <https://en.wikipedia.org/wiki/Control-flow_graph#Loop_management>
+//! <https://dl.acm.org/doi/10.1145/267959.269970> (Havlak, Nesting of reducible and irreducible loops)
+use super::{dominance::dominators, IntoNeighbors, VertexCount};
+
+/// Returns the back edges of the graph: the edges whose target dominates their
+/// source. Only edges between vertices reachable from `start` are considered.
+///
+/// Every back edge closes a natural loop headed by its target. Edges that
+/// close a cycle without such a dominating header belong to irreducible loops
+/// and are not reported here.
+#[must_use]
+pub fn back_edges<G: IntoNeighbors + VertexCount>(graph: G, start: usize) -> Vec<usize> {
+    let idoms = dominators(graph, start);
+    let mut edges = vec![];
+    for u in (0..graph.vertex_count()).filter(|&u| idoms[u].is_some()) {
+        for (v, e) in graph.neighbors(u) {
+            if dominates(&idoms, v, u) {
+                edges.push(e);
+            }
+        }
+    }
+    edges.sort_unstable();
+    edges
+}
+
+/// Returns true if `a` dominates `b`, given the immediate dominators.
+fn dominates(idoms: &[Option<usize>], a: usize, mut b: usize) -> bool {
+    loop {
+        if a == b {
+            return true;
+        }
+        match idoms[b] {
+            Some(idom) if idom != b => b = idom,
+            _ => return false,
+        }
+    }
+}
+
+/// Returns the body of the natural loop of back edge `edge`, sorted in
+/// ascending order.
+///
+/// The body is the header plus every vertex that reaches the edge's source
+/// `tail` without passing through the header. Like [`back_edges`], only
+/// vertices reachable from `start` are considered.
+///
+/// # Panics
+///
+/// Panics if `edge` isn't an outgoing edge of `tail`. In debug builds, also
+/// panics if `edge` isn't a back edge from `start`.
+#[must_use]
+pub fn natural_loop<G>(graph: G, start: usize, tail: usize, edge: usize) -> Vec<usize>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let (header, _) = graph
+        .neighbors(tail)
+        .find(|&(_, e)| e == edge)
+        .expect("edge should leave tail");
+    let idoms = dominators(graph, start);
+    debug_assert!(
+        idoms[tail].is_some() && dominates(&idoms, header, tail),
+        "edge should be a back edge"
+    );
+    let mut preds = vec![vec![]; graph.vertex_count()];
+    for u in (0..graph.vertex_count()).filter(|&u| idoms[u].is_some()) {
+        for (v, _) in graph.neighbors(u) {
+            preds[v].push(u);
+        }
+    }
+
+    let mut in_loop = vec![false; graph.vertex_count()];
+    in_loop[header] = true;
+    let mut stack = vec![];
+    if !in_loop[tail] {
+        in_loop[tail] = true;
+        stack.push(tail);
+    }
+    while let Some(u) = stack.pop() {
+        for &p in &preds[u] {
+            if !in_loop[p] {
+                in_loop[p] = true;
+                stack.push(p);
+            }
+        }
+    }
+    (0..graph.vertex_count()).filter(|&v| in_loop[v]).collect()
+}
+
+/// A single loop in a [`LoopForest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Loop {
+    /// The loop header. For irreducible loops this is the entry that is
+    /// first reached by a depth-first search.
+    pub header: usize,
+    /// Index of the innermost enclosing loop, if any.
+    pub parent: Option<usize>,
+    /// Indices of the loops directly nested in this one.
+    pub children: Vec<usize>,
+    /// Nesting depth; outermost loops have depth 1.
+    pub depth: usize,
+    /// Every vertex in the loop, including nested loops, in ascending order.
+    pub body: Vec<usize>,
+    /// Edges from the body back to the header.
+    pub back_edges: Vec<usize>,
+    /// Edges leaving the body.
+    pub exits: Vec<usize>,
+    /// False if the loop can be entered other than through its header.
+    pub reducible: bool,
+}
+
+/// The loops of a graph, organized by nesting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "LoopForestFields"))]
+pub struct LoopForest {
+    /// All loops. A loop's parent always comes before it.
+    loops: Vec<Loop>,
+    /// Maps a vertex to the innermost loop containing it.
+    innermost: Vec<Option<usize>>,
+}
+
+impl LoopForest {
+    /// Returns all loops. Enclosing loops come before the loops nested in them.
+    #[must_use]
+    pub fn loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    /// Returns the indices of the outermost loops.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.loops.len()).filter(|&i| self.loops[i].parent.is_none())
+    }
+
+    /// Returns the index of the innermost loop containing `node`.
+    #[must_use]
+    pub fn innermost_loop(&self, node: usize) -> Option<usize> {
+        self.innermost[node]
+    }
+
+    /// Returns the number of loops containing `node`.
+    #[must_use]
+    pub fn loop_depth(&self, node: usize) -> usize {
+        self.innermost[node].map_or(0, |i| self.loops[i].depth)
+    }
+}
+
+/// The fields of a deserialized [`LoopForest`], before their indices are
+/// checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct LoopForestFields {
+    /// See [`LoopForest::loops`].
+    loops: Vec<Loop>,
+    /// See [`LoopForest::innermost_loop`].
+    innermost: Vec<Option<usize>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LoopForestFields> for LoopForest {
+    type Error = String;
+
+    fn try_from(fields: LoopForestFields) -> Result<Self, Self::Error> {
+        let LoopForestFields { loops, innermost } = fields;
+        for (i, l) in loops.iter().enumerate() {
+            if l.parent
+                .is_some_and(|p| p >= i || !loops[p].children.contains(&i))
+            {
+                return Err(format!("loop {i} has an invalid parent"));
+            }
+            if l.children
+                .iter()
+                .any(|&c| loops.get(c).is_none_or(|child| child.parent != Some(i)))
+            {
+                return Err(format!("loop {i} has an invalid child"));
+            }
+            if l.header >= innermost.len() || l.body.iter().any(|&v| v >= innermost.len()) {
+                return Err(format!("loop {i} has a vertex out of range"));
+            }
+        }
+        if innermost.iter().flatten().any(|&i| i >= loops.len()) {
+            return Err("innermost loop out of range".to_owned());
+        }
+        Ok(Self { loops, innermost })
+    }
+}
+
+/// Marks a vertex that a search hasn't reached.
+const UNVISITED: usize = usize::MAX;
+
+/// Disjoint-set find with path halving.
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+/// Numbers the vertices reachable from `start` in depth-first preorder.
+/// Returns each vertex's number, the vertex for each number, and for each
+/// number the largest number in its DFS subtree.
+fn dfs_numbering<G: IntoNeighbors + VertexCount>(
+    graph: G,
+    start: usize,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let mut number = vec![UNVISITED; graph.vertex_count()];
+    let mut node = Vec::with_capacity(graph.vertex_count());
+    let mut last = vec![0; graph.vertex_count()];
+    let mut stack = vec![(start, graph.neighbors(start))];
+    number[start] = 0;
+    node.push(start);
+    while let Some((_, neighbors)) = stack.last_mut() {
+        if let Some(v) = neighbors.find_map(|(v, _)| (number[v] == UNVISITED).then_some(v)) {
+            number[v] = node.len();
+            node.push(v);
+            stack.push((v, graph.neighbors(v)));
+        } else if let Some((u, _)) = stack.pop() {
+            last[number[u]] = node.len() - 1;
+        }
+    }
+    last.truncate(node.len());
+    (number, node, last)
+}
+
+/// Fills in the back edges and exits of each loop from its body.
+fn classify_edges<G: IntoNeighbors + VertexCount>(graph: G, loops: &mut [Loop]) {
+    let mut in_body = vec![false; graph.vertex_count()];
+    for l in loops.iter_mut() {
+        for &v in &l.body {
+            in_body[v] = true;
+        }
+        for &u in &l.body {
+            for (v, e) in graph.neighbors(u) {
+                if v == l.header {
+                    l.back_edges.push(e);
+                } else if !in_body[v] {
+                    l.exits.push(e);
+                }
+            }
+        }
+        l.back_edges.sort_unstable();
+        l.exits.sort_unstable();
+        for &v in &l.body {
+            in_body[v] = false;
+        }
+    }
+}
+
+/// Builds the loop nesting forest of the subgraph reachable from `start`,
+/// using Havlak's algorithm so that irreducible loops are handled too.
+/// Time complexity: O((|V| + |E|) log |V|)
+#[must_use]
+pub fn loop_forest<G: IntoNeighbors + VertexCount>(graph: G, start: usize) -> LoopForest {
+    let (number, node, last) = dfs_numbering(graph, start);
+    let is_ancestor = |w: usize, v: usize| w <= v && v <= last[w];
+
+    // Split each vertex's predecessors into back and non-back predecessors
+    let reached = node.len();
+    let mut back_preds = vec![vec![]; reached];
+    let mut non_back_preds = vec![vec![]; reached];
+    for (u, &un) in number
+        .iter()
+        .enumerate()
+        .filter(|&(_, &un)| un != UNVISITED)
+    {
+        for (v, _) in graph.neighbors(u) {
+            let w = number[v];
+            if is_ancestor(w, un) {
+                back_preds[w].push(un);
+            } else {
+                non_back_preds[w].push(un);
+            }
+        }
+    }
+
+    // Collapse loops from the innermost outwards
+    let mut union_find = (0..reached).collect::<Vec<_>>();
+    let mut header_of = vec![None; reached];
+    let mut is_header = vec![false; reached];
+    let mut reducible = vec![true; reached];
+    let mut in_pool = vec![false; reached];
+    for w in (0..reached).rev() {
+        let mut pool = vec![];
+        let mut self_loop = false;
+        for &v in &back_preds[w] {
+            if v == w {
+                self_loop = true;
+            } else {
+                let x = find(&mut union_find, v);
+                if !in_pool[x] {
+                    in_pool[x] = true;
+                    pool.push(x);
+                }
+            }
+        }
+        let mut work_list = pool.clone();
+        while let Some(x) = work_list.pop() {
+            for i in 0..non_back_preds[x].len() {
+                let y = find(&mut union_find, non_back_preds[x][i]);
+                if !is_ancestor(w, y) {
+                    reducible[w] = false;
+                    non_back_preds[w].push(y);
+                } else if y != w && !in_pool[y] {
+                    in_pool[y] = true;
+                    pool.push(y);
+                    work_list.push(y);
+                }
+            }
+        }
+        if self_loop || !pool.is_empty() {
+            is_header[w] = true;
+            for x in pool {
+                in_pool[x] = false;
+                header_of[x] = Some(w);
+                union_find[x] = w;
+            }
+        }
+    }
+
+    // Number the loops so that parents come first
+    let mut loop_index = vec![None; reached];
+    let mut loops: Vec<Loop> = vec![];
+    for w in (0..reached).filter(|&w| is_header[w]) {
+        let parent = header_of[w].and_then(|h| loop_index[h]);
+        let index = loops.len();
+        loop_index[w] = Some(index);
+        if let Some(p) = parent {
+            loops[p].children.push(index);
+        }
+        loops.push(Loop {
+            header: node[w],
+            parent,
+            children: vec![],
+            depth: parent.map_or(1, |p: usize| loops[p].depth + 1),
+            body: vec![],
+            back_edges: vec![],
+            exits: vec![],
+            reducible: reducible[w],
+        });
+    }
+
+    let mut innermost = vec![None; graph.vertex_count()];
+    for (w, &v) in node.iter().enumerate() {
+        innermost[v] = loop_index[w].or_else(|| header_of[w].and_then(|h| loop_index[h]));
+    }
+    for (v, &inner) in innermost.iter().enumerate() {
+        let mut current = inner;
+        while let Some(i) = current {
+            loops[i].body.push(v);
+            current = loops[i].parent;
+        }
+    }
+
+    classify_edges(graph, &mut loops);
+    LoopForest { loops, innermost }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_back_edges() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 1), (2, 3), (3, 0)]);
+        assert_eq!(back_edges(&graph, 0), [2, 4]);
+        assert_eq!(natural_loop(&graph, 0, 2, 2), [1, 2]);
+        assert_eq!(natural_loop(&graph, 0, 3, 4), [0, 1, 2, 3]);
+
+        // 2 reaches the loop, but can't be reached from 0
+        let graph = Graph::from([(0, 1), (1, 0), (2, 1)]);
+        assert_eq!(back_edges(&graph, 0), [1]);
+        assert_eq!(natural_loop(&graph, 0, 1, 1), [0, 1]);
+
+        let graph = Graph::from([(0, 6), (6, 0), (0, 4), (3, 6), (4, 0), (5, 6)]);
+        assert_eq!(natural_loop(&graph, 0, 6, 1), [0, 6]);
+        assert_eq!(natural_loop(&graph, 0, 4, 4), [0, 4]);
+        assert_eq!(loop_forest(&graph, 0).loops()[0].body, [0, 4, 6]);
+
+        // irreducible: 1 and 2 can both be entered from 0
+        let graph = Graph::from([(0, 1), (0, 2), (1, 2), (2, 1)]);
+        assert!(back_edges(&graph, 0).is_empty());
+    }
+
+    #[test]
+    fn test_nested_loops() {
+        /*
+            0 -> 1 -> 2 -> 3 -> 4 -> 5
+                 ^    ^----'    |
+                 '--------------'
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (2, 3), (3, 2), (3, 4), (4, 1), (4, 5)]);
+        let forest = loop_forest(&graph, 0);
+        let loops = forest.loops();
+        assert_eq!(loops.len(), 2);
+
+        assert_eq!(loops[0].header, 1);
+        assert_eq!(loops[0].parent, None);
+        assert_eq!(loops[0].children, [1]);
+        assert_eq!(loops[0].body, [1, 2, 3, 4]);
+        assert_eq!(loops[0].back_edges, [5]);
+        assert_eq!(loops[0].exits, [6]);
+        assert!(loops[0].reducible);
+
+        assert_eq!(loops[1].header, 2);
+        assert_eq!(loops[1].parent, Some(0));
+        assert_eq!(loops[1].depth, 2);
+        assert_eq!(loops[1].body, [2, 3]);
+        assert_eq!(loops[1].back_edges, [3]);
+        assert_eq!(loops[1].exits, [4]);
+
+        assert_eq!(forest.roots().collect::<Vec<_>>(), [0]);
+        assert_eq!(
+            (0..6).map(|v| forest.loop_depth(v)).collect::<Vec<_>>(),
+            [0, 1, 2, 2, 1, 0]
+        );
+        assert_eq!(forest.innermost_loop(4), Some(0));
+    }
+
+    #[test]
+    fn test_self_and_irreducible_loops() {
+        let graph = Graph::from([(0, 0), (0, 1)]);
+        let forest = loop_forest(&graph, 0);
+        assert_eq!(forest.loops().len(), 1);
+        assert_eq!(forest.loops()[0].body, [0]);
+        assert_eq!(forest.loops()[0].back_edges, [0]);
+        assert_eq!(forest.loops()[0].exits, [1]);
+
+        let graph = Graph::from([(0, 1), (0, 2), (1, 2), (2, 1), (2, 3)]);
+        let forest = loop_forest(&graph, 0);
+        assert_eq!(forest.loops().len(), 1);
+        let l = &forest.loops()[0];
+        assert!(!l.reducible);
+        assert_eq!(l.body, [1, 2]);
+        assert_eq!(l.exits, [4]);
+        assert_eq!(forest.loop_depth(3), 0);
+    }
+}
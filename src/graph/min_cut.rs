@@ -0,0 +1,192 @@
+//! Global minimum cut (Stoer–Wagner)
+//! <https://en.wikipedia.org/wiki/Stoer%E2%80%93Wagner_algorithm>
+//!
+//! Edge directions are ignored, so this works on an [`UnGraph`] or on a
+//! [`Graph`](super::Graph) whose edges stand for undirected links.
+use super::{undirected::UnGraph, EdgeCount, IntoNeighbors, VertexCount};
+
+impl UnGraph {
+    /// Returns the weight and one side of a minimum cut. See
+    /// [`global_min_cut`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`.
+    #[must_use]
+    pub fn global_min_cut(&self, weights: &[u64]) -> Option<(u64, Vec<usize>)> {
+        global_min_cut(self, weights)
+    }
+}
+
+/// Returns the minimum total weight of edges that must be removed to split
+/// the graph in two, and the vertices on one side.
+///
+/// The side is in increasing order. Returns `None` if there are fewer than
+/// two vertices.
+///
+/// Each phase grows a set from an arbitrary vertex, always adding the vertex
+/// most tightly connected to it. The last two vertices added are merged, and
+/// the last one's connection is the minimum cut between them, so the best
+/// phase gives the global minimum. Self-loops never cross a cut.
+/// Time complexity: O(|V|^3 + |E|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`.
+pub fn global_min_cut<G>(graph: G, weights: &[u64]) -> Option<(u64, Vec<usize>)>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    let n = graph.vertex_count();
+    // Every edge is counted once, even if it's listed from both endpoints
+    let mut endpoints = vec![None; graph.edge_count()];
+    for u in 0..n {
+        for (v, e) in graph.neighbors(u) {
+            endpoints[e].get_or_insert((u, v));
+        }
+    }
+    let mut weight = vec![vec![0_u64; n]; n];
+    for (ends, &w) in endpoints.into_iter().zip(weights) {
+        if let Some((u, v)) = ends.filter(|(u, v)| u != v) {
+            weight[u][v] = weight[u][v].saturating_add(w);
+            weight[v][u] = weight[u][v];
+        }
+    }
+
+    let mut members = (0..n).map(|v| vec![v]).collect::<Vec<_>>();
+    let mut active = (0..n).collect::<Vec<_>>();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    while active.len() > 1 {
+        let mut connection = vec![0_u64; n];
+        let mut added = vec![false; n];
+        let (mut s, mut t) = (active[0], active[0]);
+        for _ in 0..active.len() {
+            let Some(&v) = active
+                .iter()
+                .filter(|&&v| !added[v])
+                .max_by_key(|&&v| (connection[v], std::cmp::Reverse(v)))
+            else {
+                break;
+            };
+            added[v] = true;
+            (s, t) = (t, v);
+            for &x in &active {
+                connection[x] = connection[x].saturating_add(weight[v][x]);
+            }
+        }
+        if best.as_ref().is_none_or(|(cut, _)| connection[t] < *cut) {
+            best = Some((connection[t], members[t].clone()));
+        }
+
+        // Merge `t` into `s`
+        let moved = std::mem::take(&mut members[t]);
+        members[s].extend(moved);
+        for &x in &active {
+            weight[s][x] = weight[s][x].saturating_add(weight[t][x]);
+            weight[x][s] = weight[s][x];
+        }
+        weight[s][s] = 0;
+        active.retain(|&v| v != t);
+    }
+    best.map(|(cut, mut side)| {
+        side.sort_unstable();
+        (cut, side)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{generators::gnp, Graph};
+
+    /// Returns the total weight of edges with exactly one endpoint in `side`.
+    fn cut_weight(graph: &Graph, weights: &[u64], side: &[bool]) -> u64 {
+        graph
+            .edges()
+            .into_iter()
+            .filter(|&(u, v, _)| side[u] != side[v])
+            .map(|(_, _, e)| weights[e])
+            .sum()
+    }
+
+    #[test]
+    fn test_global_min_cut() {
+        /*
+            The example from the Stoer–Wagner paper, with 1..8 as 0..7
+
+            0 -2- 1 -3- 2 -4- 3
+            |   / |     |   / |
+            3  2  2     2  2  2
+            | /   |     | /   |
+            4 -3- 5 -1- 6 -3- 7
+        */
+        let graph = UnGraph::from([
+            (0, 1),
+            (0, 4),
+            (1, 2),
+            (1, 4),
+            (1, 5),
+            (2, 3),
+            (2, 6),
+            (3, 6),
+            (3, 7),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+        ]);
+        let weights = [2, 3, 3, 2, 2, 4, 2, 2, 2, 3, 1, 3];
+        assert_eq!(graph.global_min_cut(&weights), Some((4, vec![2, 3, 6, 7])));
+
+        // A directed graph works too, and a disconnected one has an empty cut
+        let graph = Graph::from([(0, 1), (1, 0), (2, 3), (3, 3)]);
+        assert_eq!(global_min_cut(&graph, &[5, 5, 1, 9]), Some((0, vec![2, 3])));
+        assert_eq!(UnGraph::new(1, 0).global_min_cut(&[]), None);
+    }
+
+    #[test]
+    fn test_small() {
+        assert_eq!(
+            UnGraph::from([(0, 1)]).global_min_cut(&[7]),
+            Some((7, vec![1]))
+        );
+        assert_eq!(UnGraph::new(2, 0).global_min_cut(&[]), Some((0, vec![1])));
+
+        // Parallel edges add up, so the single edge to 2 is the cheaper cut
+        let graph = UnGraph::from([(0, 1), (1, 0), (1, 2), (2, 2)]);
+        assert_eq!(graph.global_min_cut(&[2, 3, 4, 1]), Some((4, vec![2])));
+        assert_eq!(graph.global_min_cut(&[2, 1, 4, 1]), Some((3, vec![1, 2])));
+
+        // Components with no edges between them
+        let graph = UnGraph::from([(0, 1), (2, 3), (3, 4), (4, 2)]);
+        let (cut, side) = graph.global_min_cut(&[9; 4]).unwrap();
+        assert_eq!(cut, 0);
+        assert!(side == [0, 1] || side == [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        for seed in 0..40 {
+            let n = 2 + usize::try_from(seed % 7).unwrap();
+            let graph = gnp(n, 0.4, seed);
+            let weights = (0..graph.edge_count() as u64)
+                .map(|i| (i * 7919 + seed) % 10)
+                .collect::<Vec<_>>();
+            let best = (1..(1 << n) - 1)
+                .map(|mask: usize| {
+                    let side = (0..n).map(|v| mask >> v & 1 == 1).collect::<Vec<_>>();
+                    cut_weight(&graph, &weights, &side)
+                })
+                .min();
+
+            let (cut, side) = global_min_cut(&graph, &weights).unwrap();
+            assert_eq!(Some(cut), best, "seed {seed}");
+            assert!(!side.is_empty() && side.len() < n);
+            let mut in_side = vec![false; n];
+            for v in side {
+                in_side[v] = true;
+            }
+            assert_eq!(cut_weight(&graph, &weights, &in_side), cut);
+        }
+    }
+}
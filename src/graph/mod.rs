@@ -3,13 +3,41 @@
 //! # Panics
 //!
 //! All methods will panic if given an out-of-bounds element index.
+pub mod arborescence;
+pub mod bfs;
+pub mod centrality;
+pub mod closure;
+pub mod coloring;
+pub mod csr;
 pub mod cycle;
+pub mod dag_paths;
+pub mod dfs;
 pub mod dijkstra;
 pub mod dominance;
+pub mod dot;
+pub mod feedback_arc_set;
+pub mod generators;
+pub mod heavy_light;
+pub mod io;
+pub mod isomorphism;
+pub mod lca;
+pub mod loops;
+pub mod min_cut;
+pub mod scc;
+#[cfg(feature = "serde")]
+mod serialize;
 pub mod topological_sort;
+pub mod traits;
 pub mod traversal;
+pub mod two_sat;
+pub mod undirected;
+pub mod yen;
 
-/// A compact directed-graph representation. Edges are numbered in order of insertion.
+pub use traits::{EdgeCount, IntoNeighbors, VertexCount};
+
+/// A compact directed-graph representation.
+///
+/// Edges are numbered in order of insertion.
 /// Each adjacency list consists of all edges pointing out from a given vertex.
 /// Doesn't support node/edge deletions?
 /// Space: O(|V| + |E|)
@@ -38,19 +66,19 @@ impl Graph {
 
     /// Returns the number of vertices in the graph.
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.first.len()
     }
 
     /// Returns true if the graph has no vertices.
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.first.is_empty()
     }
 
     /// Returns the number of edges in the graph.
     #[must_use]
-    pub fn edge_count(&self) -> usize {
+    pub const fn edge_count(&self) -> usize {
         self.end_vertex.len()
     }
 
@@ -63,30 +91,60 @@ impl Graph {
 
     /// Gets vertex `node`'s neighbors. These are returned in reverse order.
     #[must_use]
-    pub fn neighbors(&self, node: usize) -> NeighborIterator {
+    pub fn neighbors(&self, node: usize) -> NeighborIterator<'_> {
         NeighborIterator {
             graph: self,
             next_edge: self.first[node],
         }
     }
 
-    /// Returns a transposed version of the graph.
+    /// Returns every edge as a `(from, to, edge)` triple, in order of edge id.
+    /// Time complexity: O(|V| + |E|)
+    #[must_use]
+    pub fn edges(&self) -> Vec<(usize, usize, usize)> {
+        let mut edges = vec![(0, 0, 0); self.edge_count()];
+        for u in 0..self.len() {
+            for (v, e) in self.neighbors(u) {
+                edges[e] = (u, v, e);
+            }
+        }
+        edges
+    }
+
+    /// Returns a transposed version of the graph. Edge `e` of the transpose
+    /// is edge `e` reversed, so weights indexed by edge id still apply.
     /// <https://en.wikipedia.org/wiki/Transpose_graph>
     /// Time complexity: O(|V| + |E|)
     #[must_use]
     pub fn transpose(&self) -> Self {
         let mut graph = Self::new(self.len(), self.edge_count());
-        for &entry in &self.first {
-            if let Some(node) = entry {
-                for (v, _) in self.neighbors(node) {
-                    graph.add_edge(v, node);
-                }
-            }
+        for (u, v, _) in self.edges() {
+            graph.add_edge(v, u);
         }
         graph
     }
 }
 
+impl VertexCount for Graph {
+    fn vertex_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EdgeCount for Graph {
+    fn edge_count(&self) -> usize {
+        self.end_vertex.len()
+    }
+}
+
+impl<'a> IntoNeighbors for &'a Graph {
+    type Neighbors = NeighborIterator<'a>;
+
+    fn neighbors(self, node: usize) -> Self::Neighbors {
+        Graph::neighbors(self, node)
+    }
+}
+
 /// An iterator for convenient adjacency list traversal.
 pub struct NeighborIterator<'a> {
     /// The graph that this iterator is iterating over.
@@ -95,7 +153,7 @@ pub struct NeighborIterator<'a> {
     next_edge: Option<usize>,
 }
 
-impl<'a> Iterator for NeighborIterator<'a> {
+impl Iterator for NeighborIterator<'_> {
     type Item = (usize, usize);
 
     /// Produces an outgoing edge and vertex.
@@ -141,6 +199,14 @@ mod tests {
         let graph = Graph::from([(2, 3), (2, 4), (1, 3)]);
         assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), [(4, 1), (3, 0)]);
         let transpose = graph.transpose();
-        assert_eq!(transpose.neighbors(3).collect::<Vec<_>>(), [(1, 2), (2, 1)]);
+        assert_eq!(transpose.neighbors(3).collect::<Vec<_>>(), [(1, 2), (2, 0)]);
+
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (0, 2), (3, 1), (1, 1)]);
+        let reversed = graph
+            .edges()
+            .into_iter()
+            .map(|(u, v, e)| (v, u, e))
+            .collect::<Vec<_>>();
+        assert_eq!(graph.transpose().edges(), reversed);
     }
 }
@@ -0,0 +1,135 @@
+//! Strongly connected components and condensation
+//! <https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm>
+use super::{
+    dfs::{dfs_visit, Control, DfsEvent},
+    Graph, IntoNeighbors, VertexCount,
+};
+
+impl Graph {
+    /// Contracts each strongly connected component to a single vertex. See
+    /// [`condensation`].
+    #[must_use]
+    pub fn condensation(&self) -> (Self, Vec<usize>) {
+        condensation(self)
+    }
+}
+
+/// Returns the strongly connected components in topological order: no edge
+/// leads from a component to an earlier one.
+/// Time complexity: O(|V| + |E|)
+pub fn strongly_connected_components<G>(graph: G) -> Vec<Vec<usize>>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let n = graph.vertex_count();
+    let mut index = vec![0; n];
+    let mut low = vec![0; n];
+    let mut parent = vec![None; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut components = vec![];
+
+    dfs_visit(graph, 0..n, |event| {
+        match event {
+            DfsEvent::Discover(v, time) => {
+                (index[v], low[v]) = (time, time);
+                on_stack[v] = true;
+                stack.push(v);
+            }
+            DfsEvent::TreeEdge(u, v, _) => parent[v] = Some(u),
+            DfsEvent::BackEdge(u, v, _) | DfsEvent::ForwardOrCrossEdge(u, v, _) => {
+                if on_stack[v] {
+                    low[u] = low[u].min(index[v]);
+                }
+            }
+            DfsEvent::Finish(v, _) => {
+                if low[v] == index[v] {
+                    let start = stack.iter().rposition(|&w| w == v).unwrap_or_default();
+                    let component = stack.split_off(start);
+                    for &w in &component {
+                        on_stack[w] = false;
+                    }
+                    components.push(component);
+                }
+                if let Some(u) = parent[v] {
+                    low[u] = low[u].min(low[v]);
+                }
+            }
+        }
+        Control::Continue
+    });
+    // Tarjan's algorithm finishes a component after every component it reaches
+    components.reverse();
+    components
+}
+
+/// Contracts each strongly connected component to a single vertex.
+///
+/// Returns the condensation, a DAG whose vertices are the components in
+/// topological order, along with the component of each vertex. The
+/// condensation has no self-loops or parallel edges.
+/// Time complexity: O(|V| + |E| log |E|)
+pub fn condensation<G>(graph: G) -> (Graph, Vec<usize>)
+where
+    G: IntoNeighbors + VertexCount,
+{
+    let components = strongly_connected_components(graph);
+    let mut component = vec![0; graph.vertex_count()];
+    for (c, vertices) in components.iter().enumerate() {
+        for &v in vertices {
+            component[v] = c;
+        }
+    }
+    let mut edges = (0..graph.vertex_count())
+        .flat_map(|u| graph.neighbors(u).map(move |(v, _)| (u, v)))
+        .map(|(u, v)| (component[u], component[v]))
+        .filter(|(c, d)| c != d)
+        .collect::<Vec<_>>();
+    edges.sort_unstable();
+    edges.dedup();
+
+    let mut dag = Graph::new(components.len(), edges.len());
+    for (c, d) in edges {
+        dag.add_edge(c, d);
+    }
+    (dag, component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components() {
+        /*
+            0 <-> 1 -> 2 <-> 3 -> 4
+                       ^----------'
+            5 -> 0
+        */
+        let graph = Graph::from([
+            (0, 1),
+            (1, 0),
+            (1, 2),
+            (2, 3),
+            (3, 2),
+            (3, 4),
+            (4, 2),
+            (5, 0),
+        ]);
+        assert_eq!(
+            strongly_connected_components(&graph),
+            [vec![5], vec![0, 1], vec![2, 3, 4]]
+        );
+
+        let (dag, component) = graph.condensation();
+        assert_eq!(component, [1, 1, 2, 2, 2, 0]);
+        assert_eq!(dag.edges(), [(0, 1, 0), (1, 2, 1)]);
+        assert!(dag.is_dag());
+
+        let graph = Graph::from([(0, 0), (1, 2)]);
+        assert_eq!(
+            strongly_connected_components(&graph),
+            [vec![1], vec![2], vec![0]]
+        );
+    }
+}
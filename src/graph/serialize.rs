@@ -0,0 +1,115 @@
+//! Serde support for [`Graph`], enabled by the `serde` feature.
+//!
+//! A graph is stored as its vertex count and its edges in order of edge id,
+//! so edge ids, and any weights indexed by them, survive a round trip.
+use super::{io::MAX_READ_VERTICES, Graph};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The serialized form of a [`Graph`].
+#[derive(Serialize, Deserialize)]
+struct EdgeList {
+    /// The number of vertices.
+    vertices: usize,
+    /// `(from, to)` pairs in order of edge id.
+    edges: Vec<(usize, usize)>,
+}
+
+impl Serialize for Graph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EdgeList {
+            vertices: self.len(),
+            edges: self.edges().into_iter().map(|(u, v, _)| (u, v)).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Graph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let EdgeList { vertices, edges } = EdgeList::deserialize(deserializer)?;
+        if vertices >= MAX_READ_VERTICES {
+            return Err(de::Error::custom(format_args!(
+                "{vertices} vertices is beyond the limit of {MAX_READ_VERTICES}"
+            )));
+        }
+        let mut graph = Self::new(vertices, edges.len());
+        for (u, v) in edges {
+            if u >= vertices || v >= vertices {
+                return Err(de::Error::custom(format_args!(
+                    "edge ({u}, {v}) out of range for {vertices} vertices"
+                )));
+            }
+            graph.add_edge(u, v);
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{
+        bfs::bfs,
+        dominance::dominators,
+        loops::{loop_forest, LoopForest},
+        Graph,
+    };
+
+    #[test]
+    fn test_graph() {
+        let graph = Graph::from([(2, 3), (2, 4), (4, 1), (1, 2)]);
+        let json = serde_json::to_string(&graph).unwrap();
+        assert_eq!(json, r#"{"vertices":5,"edges":[[2,3],[2,4],[4,1],[1,2]]}"#);
+
+        let parsed: Graph = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 5);
+        assert_eq!(parsed.edges(), graph.edges());
+
+        assert!(serde_json::from_str::<Graph>(r#"{"vertices":2,"edges":[[0,2]]}"#).is_err());
+        for vertices in ["18446744073709551615", "100000000000", "1048576"] {
+            let json = format!(r#"{{"vertices":{vertices},"edges":[]}}"#);
+            assert!(serde_json::from_str::<Graph>(&json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_results() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 1)]);
+
+        let idoms = dominators(&graph, 0);
+        let json = serde_json::to_string(&idoms).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Option<usize>>>(&json).unwrap(),
+            idoms
+        );
+
+        let tree = bfs(&graph, [0]).into_tree();
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::graph::bfs::BfsTree>(&json).unwrap(),
+            tree
+        );
+
+        let forest = loop_forest(&graph, 0);
+        let json = serde_json::to_string(&forest).unwrap();
+        assert_eq!(serde_json::from_str::<LoopForest>(&json).unwrap(), forest);
+
+        // Indices that would make the accessors panic are rejected
+        let value = serde_json::to_value(&forest).unwrap();
+        for (pointer, invalid) in [
+            ("/innermost/1", 1),
+            ("/loops/0/parent", 0),
+            ("/loops/0/header", 3),
+            ("/loops/0/body/0", 7),
+        ] {
+            let mut value = value.clone();
+            *value.pointer_mut(pointer).unwrap() = invalid.into();
+            assert!(
+                serde_json::from_value::<LoopForest>(value).is_err(),
+                "{pointer}"
+            );
+        }
+        let mut value = value;
+        value["loops"][0]["children"] = serde_json::json!([0]);
+        assert!(serde_json::from_value::<LoopForest>(value).is_err());
+    }
+}
@@ -1,63 +1,58 @@
 //! Topological sort
 //! <https://en.wikipedia.org/wiki/Topological_sorting>
-// use std::{collections::HashMap, hash::Hash};
-
-// use super::Graph;
-
-// /// Kahn's topological sort algorithm.
-// /// O(|V| + |E|)
-// #[must_use]
-// pub fn kahns_algorithm<T>(graph: &Graph<T>) -> Vec<&T>
-// where
-//     T: Eq + Hash,
-// {
-//     // compute the "in-degree" for each node
-//     let mut in_degree = HashMap::<&T, u32>::with_capacity(graph.len());
-//     for neighbors in graph.values() {
-//         for node in neighbors {
-//             *in_degree.entry(node).or_insert(0) += 1;
-//         }
-//     }
-
-//     // get the nodes with no parents
-//     let mut queue: Vec<&T> = graph
-//         .keys()
-//         .filter(|x| !in_degree.contains_key(x))
-//         .collect();
-
-//     let mut ordering = Vec::with_capacity(graph.len());
-//     while let Some(parent) = queue.pop() {
-//         ordering.push(parent);
-//         if let Some(neighbors) = graph.get(parent) {
-//             for neighbor in neighbors {
-//                 if let Some(degree) = in_degree.get_mut(neighbor) {
-//                     *degree -= 1;
-//                     if *degree == 0 {
-//                         queue.push(neighbor);
-//                     }
-//                 }
-//             }
-//         }
-//     }
-//     ordering
-// }
-
-// #[cfg(test)]
-// mod tests {
-//     use std::collections::HashMap;
-
-//     use super::kahns_algorithm;
-
-//     #[test]
-//     fn test_kahn() {
-//         let graph = HashMap::from([
-//             (4, vec![1, 5]),
-//             (1, vec![5, 2]),
-//             (2, vec![3]),
-//             (5, vec![2, 3]),
-//             (3, vec![]),
-//         ]);
-
-//         assert_eq!(kahns_algorithm(&graph), vec![&4, &1, &5, &2, &3])
-//     }
-// }
+use super::{Graph, IntoNeighbors, VertexCount};
+
+impl Graph {
+    /// Returns the vertices in topological order, or `None` if the graph has
+    /// a cycle. See [`kahns_algorithm`].
+    #[must_use]
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        kahns_algorithm(self)
+    }
+}
+
+/// Kahn's topological sort algorithm. Returns `None` if the graph has a cycle.
+/// O(|V| + |E|)
+pub fn kahns_algorithm<G>(graph: G) -> Option<Vec<usize>>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    // compute the "in-degree" for each node
+    let mut in_degree = vec![0_usize; graph.vertex_count()];
+    for u in 0..graph.vertex_count() {
+        for (v, _) in graph.neighbors(u) {
+            in_degree[v] += 1;
+        }
+    }
+
+    // get the nodes with no parents
+    let mut queue = (0..graph.vertex_count())
+        .rev()
+        .filter(|&v| in_degree[v] == 0)
+        .collect::<Vec<_>>();
+
+    let mut ordering = Vec::with_capacity(graph.vertex_count());
+    while let Some(parent) = queue.pop() {
+        ordering.push(parent);
+        for (v, _) in graph.neighbors(parent) {
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                queue.push(v);
+            }
+        }
+    }
+    (ordering.len() == graph.vertex_count()).then_some(ordering)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kahn() {
+        let graph = Graph::from([(4, 1), (4, 5), (1, 5), (1, 2), (2, 3), (5, 2), (5, 3)]);
+
+        assert_eq!(graph.topological_sort(), Some(vec![0, 4, 1, 5, 2, 3]));
+        assert_eq!(Graph::from([(0, 1), (1, 0)]).topological_sort(), None);
+    }
+}
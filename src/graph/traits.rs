@@ -0,0 +1,90 @@
+//! Traits that let the graph algorithms run on any graph representation.
+//!
+//! Vertices are numbered `0..vertex_count()` and edges `0..edge_count()`, so
+//! algorithms can keep per-vertex state and per-edge weights in plain slices.
+
+/// A graph whose vertices are numbered `0..vertex_count()`.
+pub trait VertexCount {
+    /// Returns the number of vertices in the graph.
+    fn vertex_count(&self) -> usize;
+}
+
+/// A graph whose edges are numbered `0..edge_count()`.
+pub trait EdgeCount {
+    /// Returns the number of edges in the graph.
+    fn edge_count(&self) -> usize;
+}
+
+/// A graph reference that can list the outgoing edges of a vertex.
+///
+/// This is implemented for references (e.g. `&Graph`) so that the returned
+/// iterator can borrow from the graph.
+pub trait IntoNeighbors: Copy {
+    /// Iterator over `(vertex, edge)` pairs.
+    type Neighbors: Iterator<Item = (usize, usize)>;
+
+    /// Gets vertex `node`'s outgoing `(vertex, edge)` pairs.
+    fn neighbors(self, node: usize) -> Self::Neighbors;
+}
+
+impl<G: VertexCount> VertexCount for &G {
+    fn vertex_count(&self) -> usize {
+        (**self).vertex_count()
+    }
+}
+
+impl<G: EdgeCount> EdgeCount for &G {
+    fn edge_count(&self) -> usize {
+        (**self).edge_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{dijkstra::dijkstra, traversal::level_order};
+
+    /// A `width` x `height` grid where each cell points to its right and lower neighbors.
+    struct Grid {
+        width: usize,
+        height: usize,
+    }
+
+    impl VertexCount for Grid {
+        fn vertex_count(&self) -> usize {
+            self.width * self.height
+        }
+    }
+
+    impl EdgeCount for Grid {
+        fn edge_count(&self) -> usize {
+            2 * self.width * self.height
+        }
+    }
+
+    impl IntoNeighbors for &Grid {
+        type Neighbors = std::iter::Flatten<std::array::IntoIter<Option<(usize, usize)>, 2>>;
+
+        fn neighbors(self, node: usize) -> Self::Neighbors {
+            let right = (node % self.width + 1 < self.width).then_some((node + 1, 2 * node));
+            let down = (node + self.width < self.vertex_count())
+                .then_some((node + self.width, 2 * node + 1));
+            [right, down].into_iter().flatten()
+        }
+    }
+
+    #[test]
+    fn test_implicit_graph() {
+        let grid = Grid {
+            width: 3,
+            height: 2,
+        };
+        assert_eq!(
+            level_order(&grid, 0).collect::<Vec<_>>(),
+            [(1, 0), (3, 1), (2, 2), (4, 3), (4, 6), (5, 5), (5, 8)]
+        );
+
+        let weights = (0..grid.edge_count() as u64).collect::<Vec<_>>();
+        assert_eq!(dijkstra(&grid, &weights, 0), [0, 0, 2, 1, 3, 7]);
+    }
+}
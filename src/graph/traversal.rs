@@ -1,6 +1,6 @@
 //! Graph traversal algorithms.
-use super::{Graph, NeighborIterator};
-use std::collections::VecDeque;
+use super::{Graph, IntoNeighbors, VertexCount};
+use std::{borrow::BorrowMut, collections::VecDeque};
 
 impl Graph {
     /// Returns a vector of nodes in preorder traversal order.
@@ -8,33 +8,15 @@ impl Graph {
     /// Visit current node before children.
     /// Note: does not include the start node.
     #[must_use]
-    pub fn pre_order(&self, start: usize) -> PreOrderIterator {
-        let neighbors = (0..self.len())
-            .map(|node| self.neighbors(node))
-            .collect::<Vec<_>>();
-        let mut visited = vec![false; self.len()];
-        visited[start] = true;
-        PreOrderIterator {
-            stack: vec![start],
-            visited,
-            neighbors,
-        }
+    pub fn pre_order(&self, start: usize) -> PreOrderIterator<&Self> {
+        pre_order(self, start)
     }
 
     /// aka breadth first search (BFS) <https://en.wikipedia.org/wiki/Breadth-first_search>
     /// Note: does not include the start node.
     #[must_use]
-    pub fn level_order(&self, start: usize) -> LevelOrderIterator {
-        let mut visited = vec![false; self.len()];
-        visited[start] = true;
-        let queue = VecDeque::new();
-        let neighbors = self.neighbors(start);
-        LevelOrderIterator {
-            graph: self,
-            visited,
-            queue,
-            neighbors,
-        }
+    pub fn level_order(&self, start: usize) -> LevelOrderIterator<&Self> {
+        level_order(self, start)
     }
 
     /// Returns a vector of nodes in postorder traversal order.
@@ -42,42 +24,166 @@ impl Graph {
     /// by a DFS traversal.
     /// Similar to a topological sort, except it supports cycles, and provides the nodes in reverse-order.
     #[must_use]
-    pub fn post_order(&self, start: usize) -> PostOrderIterator {
-        let neighbors = (0..self.len())
-            .map(|node| self.neighbors(node))
-            .collect::<Vec<_>>();
-        let mut visited = vec![false; self.len()];
-        visited[start] = true;
-        PostOrderIterator {
-            stack: vec![start],
-            visited,
-            neighbors,
-            tail: false,
+    pub fn post_order(&self, start: usize) -> PostOrderIterator<&Self> {
+        post_order(self, start)
+    }
+}
+
+/// Visited marks that can be cleared in O(1), so that many small searches on
+/// one large graph don't each allocate and clear a whole-graph vector.
+///
+/// Each mark stores the epoch it was set in; resetting starts a new epoch.
+#[derive(Debug, Clone)]
+pub struct TraversalState {
+    /// The epoch in which each vertex was last visited.
+    marks: Vec<u32>,
+    /// Vertices marked with the current epoch are visited.
+    epoch: u32,
+}
+
+impl TraversalState {
+    /// Creates a state for graphs with up to `len` vertices. It grows as needed.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            marks: vec![0; len],
+            epoch: 1,
+        }
+    }
+
+    /// Unmarks every vertex. Amortized O(1).
+    pub fn reset(&mut self) {
+        if self.epoch == u32::MAX {
+            self.marks.fill(0);
+            self.epoch = 0;
+        }
+        self.epoch += 1;
+    }
+
+    /// Returns true if `node` has been visited since the last reset.
+    #[must_use]
+    pub fn is_visited(&self, node: usize) -> bool {
+        self.marks.get(node) == Some(&self.epoch)
+    }
+
+    /// Marks `node` as visited. Returns true if it wasn't visited already.
+    pub fn visit(&mut self, node: usize) -> bool {
+        if node >= self.marks.len() {
+            self.marks.resize(node + 1, 0);
+        }
+        let unvisited = !self.is_visited(node);
+        self.marks[node] = self.epoch;
+        unvisited
+    }
+
+    /// Resets the state for a search over `len` vertices.
+    pub(super) fn prepare(&mut self, len: usize) {
+        self.reset();
+        if self.marks.len() < len {
+            self.marks.resize(len, 0);
         }
     }
 }
 
-/// Iterator over the nodes of a graph in postorder traversal order.
-pub struct PreOrderIterator<'a> {
+impl Default for TraversalState {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Preorder depth-first traversal of any graph. See [`Graph::pre_order`].
+pub fn pre_order<G>(graph: G, start: usize) -> PreOrderIterator<G>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    pre_order_with(graph, start, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`pre_order`], but keeps its visited marks in `state`, which is
+/// reset first. Only the vertices the search reaches cost anything.
+pub fn pre_order_with<G, S>(graph: G, start: usize, mut state: S) -> PreOrderIterator<G, S>
+where
+    G: IntoNeighbors + VertexCount,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    state.borrow_mut().visit(start);
+    PreOrderIterator {
+        graph,
+        stack: vec![(start, graph.neighbors(start))],
+        state,
+    }
+}
+
+/// Breadth-first traversal of any graph. See [`Graph::level_order`].
+pub fn level_order<G>(graph: G, start: usize) -> LevelOrderIterator<G>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    level_order_with(graph, start, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`level_order`], but keeps its visited marks in `state`, which is
+/// reset first. Only the vertices the search reaches cost anything.
+pub fn level_order_with<G, S>(graph: G, start: usize, mut state: S) -> LevelOrderIterator<G, S>
+where
+    G: IntoNeighbors + VertexCount,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    state.borrow_mut().visit(start);
+    LevelOrderIterator {
+        graph,
+        state,
+        queue: VecDeque::new(),
+        neighbors: graph.neighbors(start),
+    }
+}
+
+/// Postorder depth-first traversal of any graph. See [`Graph::post_order`].
+pub fn post_order<G>(graph: G, start: usize) -> PostOrderIterator<G>
+where
+    G: IntoNeighbors + VertexCount,
+{
+    post_order_with(graph, start, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`post_order`], but keeps its visited marks in `state`, which is
+/// reset first. Only the vertices the search reaches cost anything.
+pub fn post_order_with<G, S>(graph: G, start: usize, mut state: S) -> PostOrderIterator<G, S>
+where
+    G: IntoNeighbors + VertexCount,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    state.borrow_mut().visit(start);
+    PostOrderIterator {
+        graph,
+        stack: vec![(start, None, graph.neighbors(start))],
+        state,
+    }
+}
+
+/// Iterator over the nodes of a graph in preorder traversal order.
+pub struct PreOrderIterator<G: IntoNeighbors, S = TraversalState> {
     /// The graph that this iterator is iterating over.
-    stack: Vec<usize>,
-    /// true if the node has been visited
-    visited: Vec<bool>,
-    /// neighbors of the current node
-    neighbors: Vec<NeighborIterator<'a>>,
+    graph: G,
+    /// stack of nodes being visited, with their unscanned neighbors
+    stack: Vec<(usize, G::Neighbors)>,
+    /// marks the visited nodes
+    state: S,
 }
 
-impl Iterator for PreOrderIterator<'_> {
+impl<G: IntoNeighbors, S: BorrowMut<TraversalState>> Iterator for PreOrderIterator<G, S> {
     type Item = (usize, usize);
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let &u = self.stack.last()?;
-            for (v, e) in self.neighbors[u].by_ref() {
-                if !self.visited[v] {
-                    self.visited[v] = true;
-                    self.stack.push(v);
-                    return Some((v, e));
-                }
+            let (_, neighbors) = self.stack.last_mut()?;
+            let state = self.state.borrow_mut();
+            if let Some((v, e)) = neighbors.find(|&(v, _)| !state.is_visited(v)) {
+                state.visit(v);
+                self.stack.push((v, self.graph.neighbors(v)));
+                return Some((v, e));
             }
             self.stack.pop();
         }
@@ -85,23 +191,22 @@ impl Iterator for PreOrderIterator<'_> {
 }
 
 /// Iterator over the nodes of a graph in level order traversal order.
-pub struct LevelOrderIterator<'a> {
+pub struct LevelOrderIterator<G: IntoNeighbors, S = TraversalState> {
     /// The graph that this iterator is iterating over.
-    graph: &'a Graph,
-    /// true if the node has been visited
-    visited: Vec<bool>,
+    graph: G,
+    /// marks the visited nodes
+    state: S,
     /// queue of nodes to visit
     queue: VecDeque<usize>,
     /// neighbors of the current node
-    neighbors: NeighborIterator<'a>,
+    neighbors: G::Neighbors,
 }
 
-impl Iterator for LevelOrderIterator<'_> {
+impl<G: IntoNeighbors, S: BorrowMut<TraversalState>> Iterator for LevelOrderIterator<G, S> {
     type Item = (usize, usize);
     fn next(&mut self) -> Option<(usize, usize)> {
         if let Some((neighbor, edge)) = self.neighbors.next() {
-            if !self.visited[neighbor] {
-                self.visited[neighbor] = true;
+            if self.state.borrow_mut().visit(neighbor) {
                 self.queue.push_back(neighbor);
             }
             Some((neighbor, edge))
@@ -114,41 +219,242 @@ impl Iterator for LevelOrderIterator<'_> {
 }
 
 /// Iterator over the nodes of a graph in postorder traversal order.
-pub struct PostOrderIterator<'a> {
-    /// stack of nodes to visit
-    stack: Vec<usize>,
-    /// true if the node has been visited
-    visited: Vec<bool>,
-    /// neighbors of each node
-    neighbors: Vec<NeighborIterator<'a>>,
-    /// true if the last node popped from the stack was a tail node
-    tail: bool,
-}
-
-impl Iterator for PostOrderIterator<'_> {
-    type Item = usize;
-    fn next(&mut self) -> Option<Self::Item> {
+pub struct PostOrderIterator<G: IntoNeighbors, S = TraversalState> {
+    /// The graph that this iterator is iterating over.
+    graph: G,
+    /// stack of nodes being visited, with the edges they were reached by and
+    /// their unscanned neighbors
+    stack: Vec<(usize, Option<usize>, G::Neighbors)>,
+    /// marks the visited nodes
+    state: S,
+}
+
+impl<G: IntoNeighbors, S: BorrowMut<TraversalState>> PostOrderIterator<G, S> {
+    /// Pops the next finished node and the edge it was reached by.
+    fn next_finished(&mut self) -> Option<(usize, Option<usize>)> {
         loop {
-            let &u = self.stack.last()?;
-            self.tail = true;
-            for (v, _) in self.neighbors[u].by_ref() {
-                if !self.visited[v] {
-                    self.visited[v] = true;
-                    self.stack.push(v);
-                    self.tail = false;
-                    break;
-                }
+            let (u, edge, neighbors) = self.stack.last_mut()?;
+            let state = self.state.borrow_mut();
+            if let Some((v, e)) = neighbors.find(|&(v, _)| !state.is_visited(v)) {
+                state.visit(v);
+                self.stack.push((v, Some(e), self.graph.neighbors(v)));
+            } else {
+                let finished = (*u, *edge);
+                self.stack.pop();
+                return Some(finished);
             }
-            if self.tail {
-                return self.stack.pop();
+        }
+    }
+}
+
+impl<G: IntoNeighbors, S: BorrowMut<TraversalState>> Iterator for PostOrderIterator<G, S> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_finished().map(|(u, _)| u)
+    }
+}
+
+/// Preorder depth-first traversal of every vertex, starting a new tree from
+/// each of `roots` that hasn't been visited yet.
+///
+/// Yields each vertex with the edge it was reached by. Tree roots come first
+/// in their tree and have no edge.
+pub fn pre_order_forest<G, I>(graph: G, roots: I) -> PreOrderForest<G, I::IntoIter>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+{
+    pre_order_forest_with(graph, roots, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`pre_order_forest`], but keeps its visited marks in `state`,
+/// which is reset first.
+pub fn pre_order_forest_with<G, I, S>(
+    graph: G,
+    roots: I,
+    mut state: S,
+) -> PreOrderForest<G, I::IntoIter, S>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    PreOrderForest {
+        traversal: PreOrderIterator {
+            graph,
+            stack: vec![],
+            state,
+        },
+        roots: roots.into_iter(),
+    }
+}
+
+/// Breadth-first traversal of every vertex, starting a new tree from each of
+/// `roots` that hasn't been visited yet.
+///
+/// Yields each vertex with the edge it was reached by, once per vertex. Tree
+/// roots come first in their tree and have no edge.
+pub fn level_order_forest<G, I>(graph: G, roots: I) -> LevelOrderForest<G, I::IntoIter>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+{
+    level_order_forest_with(graph, roots, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`level_order_forest`], but keeps its visited marks in `state`,
+/// which is reset first.
+pub fn level_order_forest_with<G, I, S>(
+    graph: G,
+    roots: I,
+    mut state: S,
+) -> LevelOrderForest<G, I::IntoIter, S>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    LevelOrderForest {
+        graph,
+        state,
+        queue: VecDeque::new(),
+        roots: roots.into_iter(),
+    }
+}
+
+/// Postorder depth-first traversal of every vertex, starting a new tree from
+/// each of `roots` that hasn't been visited yet.
+///
+/// Yields each vertex with the edge it was reached by. Tree roots come last
+/// in their tree and have no edge.
+pub fn post_order_forest<G, I>(graph: G, roots: I) -> PostOrderForest<G, I::IntoIter>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+{
+    post_order_forest_with(graph, roots, TraversalState::new(graph.vertex_count()))
+}
+
+/// Same as [`post_order_forest`], but keeps its visited marks in `state`,
+/// which is reset first.
+pub fn post_order_forest_with<G, I, S>(
+    graph: G,
+    roots: I,
+    mut state: S,
+) -> PostOrderForest<G, I::IntoIter, S>
+where
+    G: IntoNeighbors + VertexCount,
+    I: IntoIterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    state.borrow_mut().prepare(graph.vertex_count());
+    PostOrderForest {
+        traversal: PostOrderIterator {
+            graph,
+            stack: vec![],
+            state,
+        },
+        roots: roots.into_iter(),
+    }
+}
+
+/// Returns the next root that hasn't been visited yet, marking it visited.
+fn next_root(roots: &mut impl Iterator<Item = usize>, state: &mut TraversalState) -> Option<usize> {
+    let root = roots.find(|&root| !state.is_visited(root))?;
+    state.visit(root);
+    Some(root)
+}
+
+/// Iterator over every node of a graph in preorder traversal order.
+pub struct PreOrderForest<G: IntoNeighbors, I, S = TraversalState> {
+    /// traversal of the current tree
+    traversal: PreOrderIterator<G, S>,
+    /// candidate roots of the remaining trees
+    roots: I,
+}
+
+impl<G, I, S> Iterator for PreOrderForest<G, I, S>
+where
+    G: IntoNeighbors,
+    I: Iterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    type Item = (usize, Option<usize>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((v, e)) = self.traversal.next() {
+            return Some((v, Some(e)));
+        }
+        let root = next_root(&mut self.roots, self.traversal.state.borrow_mut())?;
+        let neighbors = self.traversal.graph.neighbors(root);
+        self.traversal.stack.push((root, neighbors));
+        Some((root, None))
+    }
+}
+
+/// Iterator over every node of a graph in level order traversal order.
+pub struct LevelOrderForest<G, I, S = TraversalState> {
+    /// The graph that this iterator is iterating over.
+    graph: G,
+    /// marks the visited nodes
+    state: S,
+    /// queue of nodes to visit, with the edges they were reached by
+    queue: VecDeque<(usize, Option<usize>)>,
+    /// candidate roots of the remaining trees
+    roots: I,
+}
+
+impl<G, I, S> Iterator for LevelOrderForest<G, I, S>
+where
+    G: IntoNeighbors,
+    I: Iterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    type Item = (usize, Option<usize>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.queue.is_empty() {
+            let root = next_root(&mut self.roots, self.state.borrow_mut())?;
+            self.queue.push_back((root, None));
+        }
+        let (u, edge) = self.queue.pop_front()?;
+        for (v, e) in self.graph.neighbors(u) {
+            if self.state.borrow_mut().visit(v) {
+                self.queue.push_back((v, Some(e)));
             }
         }
+        Some((u, edge))
+    }
+}
+
+/// Iterator over every node of a graph in postorder traversal order.
+pub struct PostOrderForest<G: IntoNeighbors, I, S = TraversalState> {
+    /// traversal of the current tree
+    traversal: PostOrderIterator<G, S>,
+    /// candidate roots of the remaining trees
+    roots: I,
+}
+
+impl<G, I, S> Iterator for PostOrderForest<G, I, S>
+where
+    G: IntoNeighbors,
+    I: Iterator<Item = usize>,
+    S: BorrowMut<TraversalState>,
+{
+    type Item = (usize, Option<usize>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.traversal.stack.is_empty() {
+            let root = next_root(&mut self.roots, self.traversal.state.borrow_mut())?;
+            let neighbors = self.traversal.graph.neighbors(root);
+            self.traversal.stack.push((root, None, neighbors));
+        }
+        self.traversal.next_finished()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::Graph;
+    use super::*;
 
     #[test]
     fn test() {
@@ -205,4 +511,92 @@ mod tests {
         ]);
         assert_eq!(graph.post_order(6).collect::<Vec<_>>(), [3, 2, 1, 5, 4, 6]);
     }
+
+    #[test]
+    fn test_forest() {
+        // two trees: 0 -> {1, 2}, 3 -> 4, and an isolated vertex 5
+        let graph = Graph::from([(0, 1), (0, 2), (3, 4), (4, 0), (5, 5)]);
+        assert_eq!(
+            pre_order_forest(&graph, 0..graph.len()).collect::<Vec<_>>(),
+            [
+                (0, None),
+                (2, Some(1)),
+                (1, Some(0)),
+                (3, None),
+                (4, Some(2)),
+                (5, None)
+            ]
+        );
+        assert_eq!(
+            level_order_forest(&graph, [3, 0, 5]).collect::<Vec<_>>(),
+            [
+                (3, None),
+                (4, Some(2)),
+                (0, Some(3)),
+                (2, Some(1)),
+                (1, Some(0)),
+                (5, None)
+            ]
+        );
+        assert_eq!(
+            post_order_forest(&graph, 0..graph.len()).collect::<Vec<_>>(),
+            [
+                (2, Some(1)),
+                (1, Some(0)),
+                (0, None),
+                (4, Some(2)),
+                (3, None),
+                (5, None)
+            ]
+        );
+        assert_eq!(pre_order_forest(&graph, [1]).count(), 1);
+    }
+
+    #[test]
+    fn test_reused_state() {
+        let graph = Graph::from([(0, 1), (1, 2), (2, 0), (3, 1)]);
+        let mut state = TraversalState::default();
+        for _ in 0..3 {
+            assert_eq!(
+                pre_order_with(&graph, 0, &mut state).collect::<Vec<_>>(),
+                [(1, 0), (2, 1)]
+            );
+            assert_eq!(
+                post_order_with(&graph, 3, &mut state).collect::<Vec<_>>(),
+                [0, 2, 1, 3]
+            );
+            assert_eq!(
+                level_order_with(&graph, 2, &mut state).collect::<Vec<_>>(),
+                [(0, 2), (1, 0), (2, 1)]
+            );
+        }
+        assert!(state.is_visited(1));
+        assert!(!state.is_visited(3));
+        state.reset();
+        assert!(!state.is_visited(1));
+        assert!(state.visit(3));
+        assert!(!state.visit(3));
+
+        for _ in 0..2 {
+            assert_eq!(
+                pre_order_forest_with(&graph, [1, 3], &mut state).collect::<Vec<_>>(),
+                [(1, None), (2, Some(1)), (0, Some(2)), (3, None)]
+            );
+            assert_eq!(
+                level_order_forest_with(&graph, [3], &mut state).collect::<Vec<_>>(),
+                [(3, None), (1, Some(3)), (2, Some(1)), (0, Some(2))]
+            );
+            assert_eq!(
+                post_order_forest_with(&graph, [2, 3], &mut state).collect::<Vec<_>>(),
+                [(1, Some(0)), (0, Some(2)), (2, None), (3, None)]
+            );
+        }
+
+        // A default state starts empty and grows to fit
+        let mut state = TraversalState::default();
+        assert!(!state.is_visited(5));
+        assert!(state.visit(5));
+        assert!(state.is_visited(5));
+        assert!(!state.is_visited(4));
+    }
 }
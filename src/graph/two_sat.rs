@@ -0,0 +1,171 @@
+//! 2-satisfiability
+//! <https://en.wikipedia.org/wiki/2-satisfiability>
+//!
+//! Each clause `a ∨ b` becomes the implications `¬a → b` and `¬b → a`. The
+//! formula is satisfiable exactly when no variable shares a strongly connected
+//! component with its negation.
+use super::{scc::strongly_connected_components, Graph};
+use std::ops::Not;
+
+/// A variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Literal(usize);
+
+impl Literal {
+    /// The literal that's true when `variable` is.
+    #[must_use]
+    pub const fn pos(variable: usize) -> Self {
+        Self(2 * variable)
+    }
+
+    /// The literal that's true when `variable` is false.
+    #[must_use]
+    pub const fn neg(variable: usize) -> Self {
+        Self(2 * variable + 1)
+    }
+
+    /// Returns the literal's variable.
+    #[must_use]
+    pub const fn variable(self) -> usize {
+        self.0 / 2
+    }
+
+    /// Returns the implication graph vertex that stands for the literal.
+    #[must_use]
+    pub const fn vertex(self) -> usize {
+        self.0
+    }
+}
+
+impl Not for Literal {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0 ^ 1)
+    }
+}
+
+/// A 2-SAT formula, built one clause at a time.
+pub struct TwoSat {
+    /// The implication graph, with vertex [`Literal::vertex`] for each literal.
+    graph: Graph,
+}
+
+impl TwoSat {
+    /// Creates a formula over `variables` variables with no clauses.
+    #[must_use]
+    pub fn new(variables: usize) -> Self {
+        Self {
+            graph: Graph::new(2 * variables, 0),
+        }
+    }
+
+    /// Returns the number of variables.
+    #[must_use]
+    pub const fn variables(&self) -> usize {
+        self.graph.len() / 2
+    }
+
+    /// Returns the implication graph.
+    #[must_use]
+    pub const fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Requires `a` or `b` to hold.
+    pub fn or(&mut self, a: Literal, b: Literal) {
+        self.graph.add_edge((!a).vertex(), b.vertex());
+        self.graph.add_edge((!b).vertex(), a.vertex());
+    }
+
+    /// Requires `a` to hold.
+    pub fn require(&mut self, a: Literal) {
+        self.or(a, a);
+    }
+
+    /// Requires `b` to hold whenever `a` does.
+    pub fn implies(&mut self, a: Literal, b: Literal) {
+        self.or(!a, b);
+    }
+
+    /// Requires exactly one of `a` and `b` to hold.
+    pub fn xor(&mut self, a: Literal, b: Literal) {
+        self.or(a, b);
+        self.or(!a, !b);
+    }
+
+    /// Requires at most one of `literals` to hold. Adds a clause for every
+    /// pair, so it's quadratic in the number of literals.
+    pub fn at_most_one(&mut self, literals: &[Literal]) {
+        for (i, &a) in literals.iter().enumerate() {
+            for &b in &literals[i + 1..] {
+                self.or(!a, !b);
+            }
+        }
+    }
+
+    /// Returns a satisfying assignment, indexed by variable, or `None` if the
+    /// formula is unsatisfiable.
+    /// Time complexity: O(|V| + |C|), for |V| variables and |C| clauses
+    #[must_use]
+    pub fn solve(&self) -> Option<Vec<bool>> {
+        let mut component = vec![0; self.graph.len()];
+        for (c, vertices) in strongly_connected_components(&self.graph)
+            .iter()
+            .enumerate()
+        {
+            for &v in vertices {
+                component[v] = c;
+            }
+        }
+        // Components are in topological order; a literal implied by its own
+        // negation comes later, so it must be the true one
+        (0..self.variables())
+            .map(|x| {
+                let (pos, neg) = (Literal::pos(x).vertex(), Literal::neg(x).vertex());
+                (component[pos] != component[neg]).then_some(component[pos] > component[neg])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that `assignment` satisfies every clause of `sat`.
+    fn assert_satisfies(sat: &TwoSat, assignment: &[bool]) {
+        let holds = |v: usize| assignment[v / 2] == v.is_multiple_of(2);
+        for (u, v, _) in sat.graph().edges() {
+            assert!(!holds(u) || holds(v), "{u} -> {v} is violated");
+        }
+    }
+
+    #[test]
+    fn test_two_sat() {
+        let (a, b, c) = (Literal::pos(0), Literal::pos(1), Literal::pos(2));
+        let mut sat = TwoSat::new(3);
+        sat.or(a, b);
+        sat.implies(a, !c);
+        sat.xor(b, c);
+        sat.require(!a);
+        let assignment = sat.solve().unwrap();
+        assert_eq!(assignment, [false, true, false]);
+        assert_satisfies(&sat, &assignment);
+
+        // c forces b off, so a must hold, which rules out c
+        sat.require(c);
+        assert_eq!(sat.solve(), None);
+
+        let mut sat = TwoSat::new(4);
+        let literals = (0..4).map(Literal::pos).collect::<Vec<_>>();
+        sat.at_most_one(&literals);
+        sat.or(literals[1], literals[3]);
+        let assignment = sat.solve().unwrap();
+        assert_eq!(assignment.iter().filter(|&&x| x).count(), 1);
+        assert_satisfies(&sat, &assignment);
+
+        assert_eq!(!Literal::neg(5), Literal::pos(5));
+        assert_eq!(Literal::neg(5).variable(), 5);
+    }
+}
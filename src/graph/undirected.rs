@@ -0,0 +1,176 @@
+//! Undirected graphs.
+//!
+//! # Panics
+//!
+//! All methods will panic if given an out-of-bounds element index.
+use super::{EdgeCount, IntoNeighbors, VertexCount};
+
+/// A compact undirected-graph representation.
+///
+/// Edges are numbered in order of insertion. Each edge is stored as two
+/// half-edges, `2 * e` pointing to its second endpoint and `2 * e + 1`
+/// pointing back to its first, each in the adjacency list of the vertex it
+/// leaves. Both report the shared edge id `e`.
+/// Space: O(|V| + |E|)
+pub struct UnGraph {
+    /// Maps a vertex id to the first half-edge in its adjacency list.
+    first: Vec<Option<usize>>,
+    /// Maps a half-edge id to the next half-edge in the same adjacency list.
+    next_half: Vec<Option<usize>>,
+    /// Maps a half-edge id to the vertex that it points to.
+    end_vertex: Vec<usize>,
+}
+
+impl UnGraph {
+    /// Initializes a graph with `vmax` vertices and no edges. To reduce
+    /// unnecessary allocations, `emax_hint` should be close to the number of
+    /// edges that will be inserted.
+    #[must_use]
+    pub fn new(vmax: usize, emax_hint: usize) -> Self {
+        Self {
+            first: vec![None; vmax],
+            next_half: Vec::with_capacity(2 * emax_hint),
+            end_vertex: Vec::with_capacity(2 * emax_hint),
+        }
+    }
+
+    /// Returns the number of vertices in the graph.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    /// Returns true if the graph has no vertices.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// Returns the number of edges in the graph.
+    #[must_use]
+    pub const fn edge_count(&self) -> usize {
+        self.end_vertex.len() / 2
+    }
+
+    /// Adds an undirected edge between `u` and `v`.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        for (from, to) in [(u, v), (v, u)] {
+            self.next_half.push(self.first[from]);
+            self.first[from] = Some(self.end_vertex.len());
+            self.end_vertex.push(to);
+        }
+    }
+
+    /// Returns the endpoints of edge `e` in the order they were added.
+    #[must_use]
+    pub fn endpoints(&self, e: usize) -> (usize, usize) {
+        (self.end_vertex[2 * e + 1], self.end_vertex[2 * e])
+    }
+
+    /// Returns the endpoint of edge `e` that isn't `node`.
+    #[must_use]
+    pub fn opposite(&self, e: usize, node: usize) -> usize {
+        let (u, v) = self.endpoints(e);
+        if node == u {
+            v
+        } else {
+            u
+        }
+    }
+
+    /// Gets vertex `node`'s neighbors with the edges to them. These are
+    /// returned in reverse order.
+    #[must_use]
+    pub fn neighbors(&self, node: usize) -> UnNeighborIterator<'_> {
+        UnNeighborIterator {
+            graph: self,
+            next_half: self.first[node],
+        }
+    }
+}
+
+/// An iterator for convenient adjacency list traversal.
+pub struct UnNeighborIterator<'a> {
+    /// The graph that this iterator is iterating over.
+    graph: &'a UnGraph,
+    /// The next half-edge in the adjacency list.
+    next_half: Option<usize>,
+}
+
+impl Iterator for UnNeighborIterator<'_> {
+    type Item = (usize, usize);
+
+    /// Produces an adjacent vertex and the edge to it.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_half.map(|h| {
+            let v = self.graph.end_vertex[h];
+            self.next_half = self.graph.next_half[h];
+            (v, h / 2)
+        })
+    }
+}
+
+impl VertexCount for UnGraph {
+    fn vertex_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EdgeCount for UnGraph {
+    fn edge_count(&self) -> usize {
+        self.end_vertex.len() / 2
+    }
+}
+
+impl<'a> IntoNeighbors for &'a UnGraph {
+    type Neighbors = UnNeighborIterator<'a>;
+
+    fn neighbors(self, node: usize) -> Self::Neighbors {
+        UnGraph::neighbors(self, node)
+    }
+}
+
+impl<const N: usize> From<[(usize, usize); N]> for UnGraph {
+    fn from(edges: [(usize, usize); N]) -> Self {
+        let vmax = edges
+            .iter()
+            .map(|&(u, v)| u.max(v))
+            .max()
+            .unwrap_or_default();
+        let mut graph = Self::new(vmax.saturating_add(1), edges.len());
+        for (u, v) in edges {
+            graph.add_edge(u, v);
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{cycle::is_dag, dijkstra::dijkstra, traversal::pre_order};
+
+    #[test]
+    fn test_graph() {
+        let graph = UnGraph::from([(2, 3), (2, 4), (4, 1)]);
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), [(4, 1), (3, 0)]);
+        assert_eq!(graph.neighbors(4).collect::<Vec<_>>(), [(1, 2), (2, 1)]);
+        assert_eq!(graph.neighbors(3).collect::<Vec<_>>(), [(2, 0)]);
+        assert_eq!(graph.endpoints(1), (2, 4));
+        assert_eq!(graph.opposite(1, 4), 2);
+    }
+
+    #[test]
+    fn test_algorithms() {
+        let graph = UnGraph::from([(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(
+            pre_order(&graph, 2).collect::<Vec<_>>(),
+            [(3, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(dijkstra(&graph, &[7, 3, 5], 3), [15, 8, 5, 0]);
+        assert!(!is_dag(&graph));
+    }
+}
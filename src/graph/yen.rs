@@ -0,0 +1,172 @@
+//! Yen's k shortest loopless paths
+//! <https://en.wikipedia.org/wiki/Yen%27s_algorithm>
+use super::{dijkstra::shortest_path, EdgeCount, Graph, IntoNeighbors, VertexCount};
+use std::collections::BTreeSet;
+
+impl Graph {
+    /// Returns up to `k` shortest loopless paths. See [`k_shortest_paths`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.edge_count()`.
+    #[must_use]
+    pub fn k_shortest_paths(
+        &self,
+        weights: &[u64],
+        start: usize,
+        end: usize,
+        k: usize,
+    ) -> Vec<(u64, Vec<usize>)> {
+        k_shortest_paths(self, weights, start, end, k)
+    }
+}
+
+/// Returns up to `k` shortest loopless paths from `start` to `end`, as
+/// `(weight, edges)` pairs in order of weight. Paths whose weight doesn't fit
+/// in a `u64` are skipped.
+///
+/// Each path after the first is found by branching off an earlier path at a
+/// "spur" vertex, with a shortest path that avoids the earlier path's prefix
+/// and the edges other known paths take from the same prefix.
+/// Time complexity: O(k |V| |E| log |V|)
+///
+/// # Panics
+///
+/// Panics if `weights.len() != graph.edge_count()`.
+pub fn k_shortest_paths<G>(
+    graph: G,
+    weights: &[u64],
+    start: usize,
+    end: usize,
+    k: usize,
+) -> Vec<(u64, Vec<usize>)>
+where
+    G: IntoNeighbors + VertexCount + EdgeCount,
+{
+    assert_eq!(graph.edge_count(), weights.len());
+    let mut head = vec![0; graph.edge_count()];
+    for u in 0..graph.vertex_count() {
+        for (v, e) in graph.neighbors(u) {
+            head[e] = v;
+        }
+    }
+    let mut banned_vertices = vec![false; graph.vertex_count()];
+    let mut banned_edges = vec![false; graph.edge_count()];
+
+    let mut found = Vec::with_capacity(k);
+    let mut candidates = BTreeSet::new();
+    candidates.extend(shortest_path(
+        graph,
+        weights,
+        start,
+        end,
+        &banned_vertices,
+        &banned_edges,
+    ));
+    while found.len() < k {
+        let Some(path) = candidates.pop_first() else {
+            break;
+        };
+        let edges = &path.1;
+
+        let mut spur = start;
+        let mut root_weight = Some(0_u64);
+        for i in 0..edges.len() {
+            let root = &edges[..i];
+            for (_, other) in found.iter().chain([&path]) {
+                if other.len() > i && other[..i] == *root {
+                    banned_edges[other[i]] = true;
+                }
+            }
+            if let Some((weight, tail)) =
+                shortest_path(graph, weights, spur, end, &banned_vertices, &banned_edges)
+            {
+                let mut candidate = root.to_vec();
+                candidate.extend(tail);
+                let total = root_weight.and_then(|w| w.checked_add(weight));
+                let known = found.iter().any(|(_, p)| *p == candidate);
+                if let (Some(total), false) = (total, known) {
+                    candidates.insert((total, candidate));
+                }
+            }
+            banned_edges.fill(false);
+
+            // The root path grows by one edge, and can't be revisited
+            banned_vertices[spur] = true;
+            root_weight = root_weight.and_then(|w| w.checked_add(weights[edges[i]]));
+            spur = head[edges[i]];
+        }
+        banned_vertices.fill(false);
+        found.push(path);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yen() {
+        // The example from Wikipedia, with C..H as 0..5
+        let graph = Graph::from([
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (2, 1),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (3, 5),
+            (4, 5),
+        ]);
+        let weights = [3, 2, 4, 1, 2, 3, 2, 1, 2];
+        let paths = graph.k_shortest_paths(&weights, 0, 5, 3);
+        assert_eq!(
+            paths,
+            [(5, vec![1, 4, 7]), (7, vec![1, 5, 8]), (8, vec![0, 2, 7])]
+        );
+
+        let paths = graph.k_shortest_paths(&weights, 0, 5, 100);
+        assert_eq!(paths.len(), 7);
+        assert!(paths.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+        assert_eq!(graph.k_shortest_paths(&weights, 5, 0, 3), []);
+        assert_eq!(graph.k_shortest_paths(&weights, 2, 2, 3), [(0, vec![])]);
+    }
+
+    #[test]
+    fn test_few_paths() {
+        /*
+            0 -> 1 -> 2, 0 -> 2 and 2 -> 0, with 3 isolated
+        */
+        let graph = Graph::from([(0, 1), (1, 2), (0, 2), (2, 0), (3, 3)]);
+        let weights = [1, 1, 3, 1, 1];
+
+        // The cycle through 0 can't be used, so there are only two paths
+        assert_eq!(
+            graph.k_shortest_paths(&weights, 0, 2, 5),
+            [(2, vec![0, 1]), (3, vec![2])]
+        );
+        assert_eq!(graph.k_shortest_paths(&weights, 0, 2, 1), [(2, vec![0, 1])]);
+        assert!(graph.k_shortest_paths(&weights, 0, 2, 0).is_empty());
+
+        // A path from a vertex to itself is empty, and never loops around
+        assert_eq!(graph.k_shortest_paths(&weights, 1, 1, 3), [(0, vec![])]);
+        assert_eq!(graph.k_shortest_paths(&weights, 3, 3, 3), [(0, vec![])]);
+
+        assert!(graph.k_shortest_paths(&weights, 0, 3, 3).is_empty());
+        assert!(graph.k_shortest_paths(&weights, 3, 0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Detouring through 3 costs more than a `u64` can hold
+        let graph = Graph::from([(0, 1), (1, 2), (1, 3), (3, 2)]);
+        let weights = [1 << 63, 1, 1 << 62, 1 << 62];
+        assert_eq!(
+            graph.k_shortest_paths(&weights, 0, 2, 3),
+            [((1 << 63) + 1, vec![0, 1])]
+        );
+    }
+}
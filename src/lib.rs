@@ -1,6 +1,6 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
-// pub mod graph;
 pub mod array;
 pub mod bitwise;
+pub mod combinatorics;
+pub mod graph;
 pub mod sort;
-pub mod combinatorics;
\ No newline at end of file